@@ -12,8 +12,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::info;
 
-use mcp_server::{McpServer, ServerMode};
-use wallet_core::Wallet;
+use mcp_server::{McpServer, ServerMode, ToolExecutor};
+use wallet_core::{RedactingWriter, Wallet};
 
 /// Symbia Labs MCP Wallet - Secure API credential manager with MCP protocol support
 #[derive(Parser, Debug)]
@@ -38,6 +38,12 @@ struct Args {
     /// Prefer using the desktop app to create a session instead.
     #[arg(long, env = "MCP_WALLET_PASSWORD", hide_env_values = true)]
     password: Option<String>,
+
+    /// Run the background spec syncs, spec file reloads and credential
+    /// health checks in this process
+    /// Leave off while the desktop app is running; it runs them itself.
+    #[arg(long)]
+    background: bool,
 }
 
 #[tokio::main]
@@ -51,6 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 tracing_subscriber::EnvFilter::from_default_env()
                     .add_directive(tracing::Level::INFO.into()),
             )
+            .with_writer(|| RedactingWriter::new(std::io::stdout()))
             .init();
     }
 
@@ -103,6 +110,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let wallet = Arc::new(RwLock::new(wallet));
 
+    if args.background {
+        // Sync remote specs that have a background sync schedule
+        tokio::spawn(Wallet::run_sync_scheduler(
+            wallet.clone(),
+            std::time::Duration::from_secs(60),
+        ));
+
+        // Reload integrations whose local spec files change
+        tokio::spawn(Wallet::run_file_watcher(
+            wallet.clone(),
+            std::time::Duration::from_secs(2),
+        ));
+
+        // Check credentials of integrations that have periodic health checks
+        let health = ToolExecutor::new(wallet.clone());
+        tokio::spawn(async move {
+            health
+                .run_health_checks(std::time::Duration::from_secs(60))
+                .await
+        });
+    }
+
     // Determine server mode
     let mode = if args.stdio {
         ServerMode::Stdio
@@ -7,7 +7,8 @@ use tracing::{debug, error, info};
 use super::capabilities::ServerCapabilities;
 use super::types::*;
 use crate::tools::{ToolExecutor, ToolGenerator};
-use wallet_core::Wallet;
+use uuid::Uuid;
+use wallet_core::{with_requester, IntegrationStatus, Wallet};
 
 /// Handler for MCP requests
 pub struct RequestHandler {
@@ -23,6 +24,10 @@ pub struct RequestHandler {
     server_version: String,
     /// Whether the session is initialized
     initialized: bool,
+    /// Session ID recorded in the credential audit log
+    session_id: Uuid,
+    /// Client name and version from the initialize request
+    client: Option<String>,
 }
 
 impl RequestHandler {
@@ -35,9 +40,20 @@ impl RequestHandler {
             server_name: "Symbia Labs MCP Wallet".to_string(),
             server_version: env!("CARGO_PKG_VERSION").to_string(),
             initialized: false,
+            session_id: Uuid::new_v4(),
+            client: None,
         }
     }
 
+    /// Describe the session and client that tool calls act for
+    fn requester(&self) -> String {
+        format!(
+            "{} (session {})",
+            self.client.as_deref().unwrap_or("unknown client"),
+            self.session_id
+        )
+    }
+
     /// Handle an incoming message
     pub async fn handle(&mut self, message: McpMessage) -> Option<McpMessage> {
         if message.is_request() {
@@ -100,6 +116,10 @@ impl RequestHandler {
         );
 
         self.initialized = true;
+        self.client = Some(format!(
+            "{} {}",
+            params.client_info.name, params.client_info.version
+        ));
 
         let result = InitializeResult {
             protocol_version: MCP_VERSION.to_string(),
@@ -130,10 +150,12 @@ impl RequestHandler {
         let mut tools = Vec::new();
 
         for integration in integrations {
+            // Disabled integrations offer no tools
+            if integration.status == IntegrationStatus::Disabled {
+                continue;
+            }
             if let Some(stored) = wallet.integrations.get_stored(&integration.key).await {
-                let integration_tools = self
-                    .tool_generator
-                    .generate_tools(&integration.key, &stored);
+                let integration_tools = self.tool_generator.generate_tools(&stored);
                 tools.extend(integration_tools);
             }
         }
@@ -152,17 +174,18 @@ impl RequestHandler {
 
         debug!("Calling tool: {}", params.name);
 
-        let result = self
-            .tool_executor
-            .execute(&params.name, params.arguments)
-            .await;
+        let result = with_requester(
+            self.requester(),
+            self.tool_executor.execute(&params.name, params.arguments),
+        )
+        .await;
 
         match result {
             Ok(tool_result) => serde_json::to_value(tool_result)
                 .map_err(|e| McpError::internal_error(e.to_string())),
             Err(e) => {
                 error!("Tool execution failed: {}", e);
-                let error_result = ToolCallResult::error(e.to_string());
+                let error_result = ToolCallResult::error(e.redacted());
                 serde_json::to_value(error_result)
                     .map_err(|e| McpError::internal_error(e.to_string()))
             }
@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use wallet_core::redact;
 
 /// JSON-RPC version string
 pub const JSONRPC_VERSION: &str = "2.0";
@@ -175,17 +176,21 @@ pub struct ToolCallResult {
 }
 
 impl ToolCallResult {
+    /// Text result (secrets are redacted)
     pub fn text(text: impl Into<String>) -> Self {
         Self {
-            content: vec![ToolContent::Text { text: text.into() }],
+            content: vec![ToolContent::Text {
+                text: redact(&text.into()),
+            }],
             is_error: None,
         }
     }
 
+    /// Error result (secrets are redacted)
     pub fn error(message: impl Into<String>) -> Self {
         Self {
             content: vec![ToolContent::Text {
-                text: message.into(),
+                text: redact(&message.into()),
             }],
             is_error: Some(true),
         }
@@ -1,14 +1,81 @@
 //! Execute MCP tools by making HTTP requests
 
-use openapi_parser::{ApiOperation, HttpMethod, ParameterLocation};
-use reqwest::Client;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use openapi_parser::{ApiKeyLocation, ApiOperation, AuthScheme, HttpMethod, ParameterLocation};
+use reqwest::{Certificate, Client, Identity, RequestBuilder, StatusCode};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
+use super::signing::{AwsSigV4Signer, HmacSigner, RequestSigner};
+use super::CREDENTIAL_ARGUMENT;
 use crate::protocol::ToolCallResult;
-use wallet_core::{Wallet, WalletError};
+use wallet_core::{
+    redact, Credential, CredentialType, DecryptedCredential, HealthOutcome, Integration,
+    IntegrationStatus, SigningConfig, StoredIntegration, TokenLookup, Wallet, WalletError,
+};
+
+/// Timeout for requests to integration APIs
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Credentials to attach to an outgoing request
+#[derive(Default)]
+struct RequestAuth {
+    headers: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    cookies: Vec<(String, String)>,
+    /// Signs the final request instead of (or in addition to) the above
+    signer: Option<Box<dyn RequestSigner>>,
+}
+
+impl RequestAuth {
+    /// Send a single Authorization header
+    fn authorization(value: String) -> Self {
+        Self {
+            headers: vec![("Authorization".to_string(), value)],
+            ..Default::default()
+        }
+    }
+
+    /// Add the credentials to a request
+    fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if !self.query.is_empty() {
+            request = request.query(&self.query);
+        }
+        if !self.cookies.is_empty() {
+            let cookie = self
+                .cookies
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join("; ");
+            request = request.header("Cookie", cookie);
+        }
+        request
+    }
+}
+
+/// Secret lookups that may need a request to an OAuth2 token endpoint
+#[derive(Clone, Copy)]
+enum SecretLookup<'a> {
+    /// The current secret, refreshing an expiring OAuth2 token
+    Current { token_url: Option<&'a str> },
+    /// A refreshed OAuth2 token, even if the current one has not expired
+    Refreshed { token_url: Option<&'a str> },
+    /// A client credentials token for a set of scopes
+    ClientCredentials {
+        token_url: Option<&'a str>,
+        scopes: &'a [String],
+    },
+}
 
 /// Executor for MCP tools
 pub struct ToolExecutor {
@@ -16,17 +83,24 @@ pub struct ToolExecutor {
     wallet: Arc<RwLock<Wallet>>,
     /// HTTP client
     client: Client,
+    /// Clients presenting a TLS client certificate, keyed by integration and
+    /// credential, with the fingerprint of the certificate they were built for
+    mtls_clients: RwLock<HashMap<(String, Uuid), (String, Client)>>,
 }
 
 impl ToolExecutor {
     /// Create a new tool executor
     pub fn new(wallet: Arc<RwLock<Wallet>>) -> Self {
         let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(REQUEST_TIMEOUT)
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { wallet, client }
+        Self {
+            wallet,
+            client,
+            mtls_clients: RwLock::new(HashMap::new()),
+        }
     }
 
     /// Execute a tool by name
@@ -35,91 +109,707 @@ impl ToolExecutor {
         tool_name: &str,
         arguments: Option<Value>,
     ) -> Result<ToolCallResult, WalletError> {
-        // Parse tool name: {integration}_{operation_path}
-        let (integration_key, operation_path) = self.parse_tool_name(tool_name)?;
+        debug!("Executing tool: {}", tool_name);
 
-        debug!(
-            "Executing tool: {} -> {} / {}",
-            tool_name, integration_key, operation_path
-        );
+        let (status, _, response_text) = self.call(tool_name, arguments).await?;
+        Ok(Self::format_response(status, response_text))
+    }
+
+    /// Check that an integration's credential works by calling its probe operation
+    ///
+    /// The probe's response is recorded on the integration status like any
+    /// request; a probe that cannot be sent marks the integration as errored.
+    /// Either way the next periodic check is scheduled. An explicit label
+    /// checks that binding instead of the default credential.
+    pub async fn validate_credential(
+        &self,
+        integration_key: &str,
+        label: Option<&str>,
+    ) -> Result<Integration, WalletError> {
+        let error = match self.probe(integration_key, label).await {
+            Ok(health) => {
+                info!("Validated credential for {}: {:?}", integration_key, health);
+                None
+            }
+            Err(WalletError::WalletLocked) => return Err(WalletError::WalletLocked),
+            // Recorded as unreachable by the call
+            Err(WalletError::ConnectionError(reason)) => {
+                info!(
+                    "Validated credential for {}: unreachable ({})",
+                    integration_key, reason
+                );
+                None
+            }
+            Err(e) => Some(format!("Credential check failed: {}", e.redacted())),
+        };
+
+        self.wallet
+            .read()
+            .await
+            .integrations
+            .record_validation(integration_key, error)
+            .await
+    }
+
+    /// Call an integration's probe operation and return what it tells about its health
+    async fn probe(
+        &self,
+        integration_key: &str,
+        label: Option<&str>,
+    ) -> Result<HealthOutcome, WalletError> {
+        let stored = self
+            .wallet
+            .read()
+            .await
+            .integrations
+            .get_stored(integration_key)
+            .await
+            .ok_or_else(|| WalletError::IntegrationNotFound(integration_key.to_string()))?;
+        let probe = stored.probe_operation().ok_or_else(|| {
+            WalletError::OperationNotFound(format!(
+                "No probe operation for integration {}",
+                integration_key
+            ))
+        })?;
+
+        let tool_name = stored.operation_tool_name(&probe).ok_or_else(|| {
+            WalletError::OperationNotFound(format!(
+                "Probe operation {} of integration {}",
+                probe, integration_key
+            ))
+        })?;
+
+        let arguments = label.map(|label| serde_json::json!({ CREDENTIAL_ARGUMENT: label }));
+        let (_, health, _) = self.call(tool_name, arguments).await?;
+        Ok(health)
+    }
 
+    /// Check credentials of integrations whose periodic check is due, every `tick`
+    ///
+    /// Runs until the task is dropped; ticks are skipped while the wallet is locked.
+    pub async fn run_health_checks(&self, tick: Duration) {
+        let mut interval = tokio::time::interval(tick);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let due = {
+                let wallet = self.wallet.read().await;
+                if !wallet.is_unlocked() {
+                    continue;
+                }
+                wallet.integrations.health_checks_due().await
+            };
+
+            for key in due {
+                match self.validate_credential(&key, None).await {
+                    Ok(integration) => debug!("Health check of {}: {:?}", key, integration.status),
+                    Err(e) => warn!("Health check of {} failed: {}", key, e.redacted()),
+                }
+            }
+        }
+    }
+
+    /// Call the operation behind a tool and return the response status, health and body
+    async fn call(
+        &self,
+        tool_name: &str,
+        arguments: Option<Value>,
+    ) -> Result<(StatusCode, HealthOutcome, String), WalletError> {
         let wallet = self.wallet.read().await;
 
         if !wallet.is_unlocked() {
             return Err(WalletError::WalletLocked);
         }
 
+        // Find the integration and operation behind the tool
+        let (integration_key, index) = wallet
+            .integrations
+            .resolve_tool(tool_name)
+            .await
+            .ok_or_else(|| WalletError::OperationNotFound(tool_name.to_string()))?;
+        let integration_key = integration_key.as_str();
+
         // Get integration
         let stored = wallet
             .integrations
-            .get_stored(&integration_key)
+            .get_stored(integration_key)
             .await
-            .ok_or_else(|| WalletError::IntegrationNotFound(integration_key.clone()))?;
+            .ok_or_else(|| WalletError::IntegrationNotFound(integration_key.to_string()))?;
+
+        if stored.integration.status == IntegrationStatus::Disabled {
+            return Err(WalletError::IntegrationDisabled(
+                integration_key.to_string(),
+            ));
+        }
 
-        // Find operation
         let operation = stored
-            .lookup_operation(&operation_path)
-            .ok_or_else(|| WalletError::OperationNotFound(operation_path.clone()))?;
+            .tool_operation(index, tool_name)
+            .ok_or_else(|| WalletError::OperationNotFound(tool_name.to_string()))?;
+        let operation_path = operation.normalized_id.as_str();
 
-        // Get credential
-        let credential_id = stored.integration.credential_id.ok_or_else(|| {
-            WalletError::CredentialNotFound(format!(
-                "No credential for integration {}",
-                integration_key
-            ))
-        })?;
+        let mut args_map = match arguments {
+            Some(Value::Object(map)) => map,
+            Some(_) => {
+                return Err(WalletError::ParseError(
+                    "Arguments must be an object".to_string(),
+                ))
+            }
+            None => serde_json::Map::new(),
+        };
+
+        // Pick the credential, honoring an explicit label
+        let label = match args_map.remove(CREDENTIAL_ARGUMENT) {
+            Some(Value::String(label)) => Some(label),
+            Some(_) => {
+                return Err(WalletError::ParseError(format!(
+                    "{} must be a string",
+                    CREDENTIAL_ARGUMENT
+                )))
+            }
+            None => None,
+        };
+        let binding = stored.integration.select_binding(label.as_deref())?;
+        let server_url = binding
+            .and_then(|b| b.server_url.clone())
+            .unwrap_or_else(|| stored.integration.server_url.clone());
+        let credential_id = binding
+            .map(|b| b.credential_id)
+            .or(stored.integration.credential_id);
 
-        let decrypted = wallet.credentials.get_decrypted(credential_id).await?;
-        let api_key = decrypted.expose().to_string();
+        // Public operations are called without a credential
+        let credential = if operation.public {
+            debug!("Operation {} requires no authentication", operation_path);
+            None
+        } else {
+            let credential_id = credential_id.ok_or_else(|| {
+                WalletError::CredentialNotFound(format!(
+                    "No credential for integration {}",
+                    integration_key
+                ))
+            })?;
+
+            let Some(credential) = wallet.credentials.get(credential_id).await? else {
+                // Forget any client built for a deleted certificate
+                self.mtls_clients
+                    .write()
+                    .await
+                    .retain(|(_, id), _| *id != credential_id);
+                return Err(WalletError::CredentialNotFound(credential_id.to_string()));
+            };
 
-        // Drop wallet lock before making HTTP request
+            Self::check_scopes(&stored, operation, &credential)?;
+            Some(credential)
+        };
+
+        // Drop wallet lock before token requests and the HTTP request
         drop(wallet);
 
+        let auth = match &credential {
+            Some(credential) => self.request_auth(&stored, operation, credential).await?,
+            None => RequestAuth::default(),
+        };
+        let client = match &credential {
+            Some(credential) if credential.credential_type == CredentialType::ClientCertificate => {
+                self.mtls_client(integration_key, credential).await?
+            }
+            _ => self.client.clone(),
+        };
+
         // Build and execute request
-        let result = self
-            .execute_operation(
-                &stored.integration.server_url,
-                operation,
-                arguments,
-                &api_key,
-            )
-            .await?;
+        let outcome = async {
+            let (mut status, mut health, mut response_text) = self
+                .execute_operation(&client, &server_url, operation, &args_map, &auth)
+                .await?;
+
+            // Retry once with a renewed token if the server rejected the access token
+            if let (StatusCode::UNAUTHORIZED, Some(credential)) = (status, &credential) {
+                let renewed = self.renew_secret(&stored, operation, credential).await?;
+                if let Some(renewed) = renewed {
+                    info!(
+                        "Got 401 for {}, retrying with a renewed token",
+                        operation_path
+                    );
+                    let scheme = Self::secret_scheme(&stored, operation, credential)?;
+                    let auth = Self::place_secret(&scheme, &renewed);
 
-        Ok(result)
+                    (status, health, response_text) = self
+                        .execute_operation(&client, &server_url, operation, &args_map, &auth)
+                        .await?;
+                }
+            }
+
+            Ok::<_, WalletError>((status, health, response_text))
+        }
+        .await;
+
+        // Update the integration status from what happened
+        let health = match &outcome {
+            Ok((_, health, _)) => Some(health.clone()),
+            Err(WalletError::ConnectionError(reason)) => {
+                Some(HealthOutcome::Unreachable(reason.clone()))
+            }
+            Err(_) => None,
+        };
+        if let Some(health) = health {
+            let wallet = self.wallet.read().await;
+            if let Err(e) = wallet
+                .integrations
+                .record_health(integration_key, &health)
+                .await
+            {
+                warn!(
+                    "Failed to record health of integration {}: {}",
+                    integration_key, e
+                );
+            }
+        }
+
+        if let Some(credential) = &credential {
+            let status = outcome.as_ref().ok().map(|(status, _, _)| status.as_u16());
+            let wallet = self.wallet.read().await;
+            if let Err(e) = wallet
+                .credentials
+                .record_usage(credential.id, integration_key, operation_path, status)
+                .await
+            {
+                warn!(
+                    "Failed to record usage of credential {}: {}",
+                    credential.id, e
+                );
+            }
+        }
+
+        outcome
+    }
+
+    /// Check that an OAuth2 token was granted the scopes an operation requires
+    ///
+    /// Tokens whose granted scopes are unknown are sent as-is.
+    fn check_scopes(
+        stored: &StoredIntegration,
+        operation: &ApiOperation,
+        credential: &Credential,
+    ) -> Result<(), WalletError> {
+        let Some(granted) = &credential.granted_scopes else {
+            return Ok(());
+        };
+        if credential.credential_type != CredentialType::OAuth2Token {
+            return Ok(());
+        }
+
+        let missing: Vec<String> = stored
+            .oauth2_scopes(operation)
+            .into_iter()
+            .filter(|scope| !granted.contains(scope))
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(WalletError::InsufficientScope(missing.join(" ")))
+        }
     }
 
-    /// Parse tool name into integration key and operation path
-    fn parse_tool_name(&self, tool_name: &str) -> Result<(String, String), WalletError> {
-        // Tool name format: {integration}_{operation_path_with_underscores}
-        // e.g., "stripe_customers_create" -> ("stripe", "customers.create")
+    /// Resolve the credentials to attach to a request for an operation
+    async fn request_auth(
+        &self,
+        stored: &StoredIntegration,
+        operation: &ApiOperation,
+        credential: &Credential,
+    ) -> Result<RequestAuth, WalletError> {
+        // Client certificates are presented during the TLS handshake
+        if credential.credential_type == CredentialType::ClientCertificate {
+            return Ok(RequestAuth::default());
+        }
+
+        if let Some(signing) = &stored.integration.signing {
+            let wallet = self.wallet.read().await;
+            return Ok(RequestAuth {
+                signer: Some(Self::signer(&wallet, signing, credential).await?),
+                ..Default::default()
+            });
+        }
 
-        let parts: Vec<&str> = tool_name.splitn(2, '_').collect();
-        if parts.len() != 2 {
-            return Err(WalletError::OperationNotFound(format!(
-                "Invalid tool name format: {}",
-                tool_name
+        if credential.credential_type != CredentialType::MultiField {
+            let scheme = Self::secret_scheme(stored, operation, credential)?;
+            let secret = self
+                .credential_secret(stored, operation, credential)
+                .await?;
+            return Ok(Self::place_secret(&scheme, &secret));
+        }
+
+        let targets = stored.credential_field_targets(&credential.field_names);
+        if targets.is_empty() {
+            return Err(WalletError::InvalidCredential(format!(
+                "No fields of credential {} map to a security scheme of {}",
+                credential.id, stored.integration.key
             )));
         }
 
-        let integration_key = parts[0].to_string();
-        let operation_path = parts[1].replace('_', ".");
+        let fields = self
+            .wallet
+            .read()
+            .await
+            .credentials
+            .get_fields(credential.id)
+            .await?;
+        let mut auth = RequestAuth::default();
+        for target in targets {
+            if let Some(value) = fields.get(&target.field) {
+                let param = (target.name, value.expose().to_string());
+                match target.location {
+                    ApiKeyLocation::Header => auth.headers.push(param),
+                    ApiKeyLocation::Query => auth.query.push(param),
+                    ApiKeyLocation::Cookie => auth.cookies.push(param),
+                }
+            }
+        }
+
+        Ok(auth)
+    }
+
+    /// Get the HTTP client that presents a credential's client certificate
+    ///
+    /// Clients are built once per integration and credential and reused
+    /// until the certificate, key or CA bundle of the credential changes.
+    async fn mtls_client(
+        &self,
+        integration_key: &str,
+        credential: &Credential,
+    ) -> Result<Client, WalletError> {
+        let material = self
+            .wallet
+            .read()
+            .await
+            .credentials
+            .get_client_certificate(credential.id)
+            .await?;
+
+        let mut hasher = Sha256::new();
+        for part in [
+            Some(&material.private_key),
+            Some(&material.certificate),
+            material.ca_bundle.as_ref(),
+        ] {
+            hasher.update(part.map(|p| p.expose()).unwrap_or_default());
+            hasher.update([0]);
+        }
+        let fingerprint = hex::encode(hasher.finalize());
+
+        let cache_key = (integration_key.to_string(), credential.id);
+        if let Some((built_for, client)) = self.mtls_clients.read().await.get(&cache_key) {
+            if *built_for == fingerprint {
+                return Ok(client.clone());
+            }
+        }
+
+        let invalid = |e: reqwest::Error| {
+            WalletError::InvalidCredential(format!(
+                "Client certificate {} is not usable: {}",
+                credential.id, e
+            ))
+        };
+
+        let identity_pem = DecryptedCredential::new(format!(
+            "{}\n{}",
+            material.private_key.expose(),
+            material.certificate.expose()
+        ));
+        let identity = Identity::from_pem(identity_pem.expose().as_bytes()).map_err(invalid)?;
+
+        let mut builder = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .identity(identity);
+        if let Some(ca_bundle) = &material.ca_bundle {
+            for certificate in
+                Certificate::from_pem_bundle(ca_bundle.expose().as_bytes()).map_err(invalid)?
+            {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        let client = builder.build().map_err(invalid)?;
+
+        debug!(
+            "Built mTLS client for {} with credential {}",
+            integration_key, credential.id
+        );
+        self.mtls_clients
+            .write()
+            .await
+            .insert(cache_key, (fingerprint, client.clone()));
+        Ok(client)
+    }
+
+    /// Build the request signer configured for an integration
+    async fn signer(
+        wallet: &Wallet,
+        signing: &SigningConfig,
+        credential: &Credential,
+    ) -> Result<Box<dyn RequestSigner>, WalletError> {
+        let invalid = |expected: &str| {
+            WalletError::InvalidCredential(format!(
+                "Credential {} cannot sign requests: {}",
+                credential.id, expected
+            ))
+        };
+
+        match (signing, credential.credential_type) {
+            (SigningConfig::AwsSigV4 { region, service }, CredentialType::MultiField) => {
+                let fields = wallet.credentials.get_fields(credential.id).await?;
+                let field = |name: &str| fields.get(name).map(|v| v.expose());
+
+                let (Some(access_key_id), Some(secret_access_key)) =
+                    (field("access_key_id"), field("secret_access_key"))
+                else {
+                    return Err(invalid("needs access_key_id and secret_access_key fields"));
+                };
+
+                Ok(Box::new(AwsSigV4Signer::new(
+                    access_key_id,
+                    secret_access_key,
+                    field("session_token"),
+                    region,
+                    service,
+                )))
+            }
+            (SigningConfig::AwsSigV4 { .. }, _) => {
+                Err(invalid("AWS SigV4 needs a multi-field credential"))
+            }
+            (
+                SigningConfig::HmacSha256 {
+                    signature_header,
+                    timestamp_header,
+                    key_id_header,
+                },
+                credential_type,
+            ) => {
+                let (secret, key_id) = match credential_type {
+                    CredentialType::ApiKey | CredentialType::External => {
+                        let secret = wallet.credentials.get_decrypted(credential.id).await?;
+                        (secret.expose().to_string(), None)
+                    }
+                    CredentialType::MultiField => {
+                        let fields = wallet.credentials.get_fields(credential.id).await?;
+                        let secret = fields
+                            .get("secret")
+                            .ok_or_else(|| invalid("needs a secret field"))?;
+                        (
+                            secret.expose().to_string(),
+                            fields.get("key_id").map(|v| v.expose().to_string()),
+                        )
+                    }
+                    _ => return Err(invalid("HMAC needs an API key or multi-field credential")),
+                };
+
+                let signer = HmacSigner::new(&secret, signature_header, timestamp_header);
+                Ok(Box::new(match (key_id, key_id_header) {
+                    (Some(key_id), Some(header)) => signer.with_key_id(&key_id, header),
+                    _ => signer,
+                }))
+            }
+        }
+    }
+
+    /// Get the secret to send for an operation, refreshing or minting OAuth2 tokens as needed
+    async fn credential_secret(
+        &self,
+        stored: &StoredIntegration,
+        operation: &ApiOperation,
+        credential: &Credential,
+    ) -> Result<DecryptedCredential, WalletError> {
+        match credential.credential_type {
+            CredentialType::OAuth2ClientCredentials => {
+                let token_url = stored.client_credentials_token_url();
+                let scopes = stored.oauth2_scopes(operation);
+                self.lookup_secret(
+                    credential.id,
+                    SecretLookup::ClientCredentials {
+                        token_url: token_url.as_deref(),
+                        scopes: &scopes,
+                    },
+                )
+                .await
+            }
+            CredentialType::BasicAuth => {
+                let (username, password) = self
+                    .wallet
+                    .read()
+                    .await
+                    .credentials
+                    .get_basic_auth(credential.id)
+                    .await?;
+                let pair = DecryptedCredential::new(format!(
+                    "{}:{}",
+                    username.expose(),
+                    password.expose()
+                ));
+                Ok(DecryptedCredential::new(BASE64.encode(pair.expose())))
+            }
+            _ => {
+                let token_url = stored.oauth2_token_url();
+                self.lookup_secret(
+                    credential.id,
+                    SecretLookup::Current {
+                        token_url: token_url.as_deref(),
+                    },
+                )
+                .await
+            }
+        }
+    }
+
+    /// Look up a secret, sending any token request without holding the wallet lock
+    async fn lookup_secret(
+        &self,
+        id: Uuid,
+        lookup: SecretLookup<'_>,
+    ) -> Result<DecryptedCredential, WalletError> {
+        loop {
+            let wallet = self.wallet.read().await;
+            let credentials = &wallet.credentials;
+            let step = match lookup {
+                SecretLookup::Current { token_url } => {
+                    credentials.lookup_with_refresh(id, token_url).await?
+                }
+                SecretLookup::Refreshed { token_url } => {
+                    credentials.lookup_refreshed(id, token_url).await?
+                }
+                SecretLookup::ClientCredentials { token_url, scopes } => {
+                    credentials
+                        .lookup_client_credentials_token(id, token_url, scopes)
+                        .await?
+                }
+            };
+            drop(wallet);
+
+            match step {
+                TokenLookup::Ready(secret) => return Ok(secret),
+                TokenLookup::Busy(wait) => wait.wait().await,
+                TokenLookup::Fetch(request) => {
+                    let fetched = request.send().await?;
+                    return self
+                        .wallet
+                        .read()
+                        .await
+                        .credentials
+                        .store_token(fetched)
+                        .await;
+                }
+            }
+        }
+    }
 
-        Ok((integration_key, operation_path))
+    /// Pick the scheme an operation accepts a credential's secret with
+    fn secret_scheme(
+        stored: &StoredIntegration,
+        operation: &ApiOperation,
+        credential: &Credential,
+    ) -> Result<AuthScheme, WalletError> {
+        stored
+            .credential_auth_scheme(operation, credential.credential_type)
+            .ok_or_else(|| {
+                WalletError::InvalidCredential(format!(
+                    "Operation {} does not accept {:?} credential {}",
+                    operation.normalized_id, credential.credential_type, credential.id
+                ))
+            })
     }
 
-    /// Execute an HTTP operation
+    /// Place a credential secret in the request as the auth scheme requires
+    fn place_secret(scheme: &AuthScheme, secret: &DecryptedCredential) -> RequestAuth {
+        let secret = secret.expose();
+
+        if let (Some(name), Some(value)) = (scheme.header_name(), scheme.format_header(secret)) {
+            return RequestAuth {
+                headers: vec![(name.to_string(), value)],
+                ..Default::default()
+            };
+        }
+        if let Some(name) = scheme.query_param() {
+            return RequestAuth {
+                query: vec![(name.to_string(), secret.to_string())],
+                ..Default::default()
+            };
+        }
+        if let Some(name) = scheme.cookie_name() {
+            return RequestAuth {
+                cookies: vec![(name.to_string(), secret.to_string())],
+                ..Default::default()
+            };
+        }
+
+        // No usable scheme declared: fall back to a bearer token
+        RequestAuth::authorization(format!("Bearer {}", secret))
+    }
+
+    /// Renew a token the API rejected, or `None` if the credential cannot be renewed
+    async fn renew_secret(
+        &self,
+        stored: &StoredIntegration,
+        operation: &ApiOperation,
+        credential: &Credential,
+    ) -> Result<Option<DecryptedCredential>, WalletError> {
+        match credential.credential_type {
+            CredentialType::OAuth2ClientCredentials => {
+                self.wallet
+                    .read()
+                    .await
+                    .credentials
+                    .invalidate_cached_tokens(credential.id)
+                    .await;
+                self.credential_secret(stored, operation, credential)
+                    .await
+                    .map(Some)
+            }
+            CredentialType::OAuth2Token => {
+                let token_url = stored.oauth2_token_url();
+                if !self
+                    .wallet
+                    .read()
+                    .await
+                    .credentials
+                    .can_refresh(credential.id, token_url.as_deref())
+                    .await?
+                {
+                    return Ok(None);
+                }
+                self.lookup_secret(
+                    credential.id,
+                    SecretLookup::Refreshed {
+                        token_url: token_url.as_deref(),
+                    },
+                )
+                .await
+                .map(Some)
+            }
+            // The secret may have been rotated in its store since it was cached
+            CredentialType::External => {
+                let wallet = self.wallet.read().await;
+                wallet
+                    .credentials
+                    .invalidate_cached_tokens(credential.id)
+                    .await;
+                wallet
+                    .credentials
+                    .get_decrypted(credential.id)
+                    .await
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Execute an HTTP operation and return the response status, health and body
     async fn execute_operation(
         &self,
+        client: &Client,
         base_url: &str,
         operation: &ApiOperation,
-        arguments: Option<Value>,
-        api_key: &str,
-    ) -> Result<ToolCallResult, WalletError> {
-        let args = arguments.unwrap_or(Value::Object(serde_json::Map::new()));
-        let args_map = args
-            .as_object()
-            .ok_or_else(|| WalletError::ParseError("Arguments must be an object".to_string()))?;
-
+        args_map: &serde_json::Map<String, Value>,
+        auth: &RequestAuth,
+    ) -> Result<(StatusCode, HealthOutcome, String), WalletError> {
         // Build URL with path parameters substituted
         let mut url = format!("{}{}", base_url.trim_end_matches('/'), operation.path);
         for param in &operation.parameters {
@@ -162,15 +852,15 @@ impl ToolExecutor {
             HttpMethod::Trace => reqwest::Method::TRACE,
         };
 
-        let mut request = self.client.request(method.clone(), &url);
+        let mut request = client.request(method.clone(), &url);
 
         // Add query parameters
         if !query_params.is_empty() {
             request = request.query(&query_params);
         }
 
-        // Add authentication header (assume bearer token for now)
-        request = request.header("Authorization", format!("Bearer {}", api_key));
+        // Add credentials
+        request = auth.apply(request);
 
         // Add body for POST/PUT/PATCH
         let body_for_logging: Option<serde_json::Map<String, Value>>;
@@ -217,9 +907,9 @@ impl ToolExecutor {
             debug!("Final body keys: {:?}", body.keys().collect::<Vec<_>>());
 
             if !body.is_empty() {
-                info!(
+                debug!(
                     "Request body: {}",
-                    serde_json::to_string(&body).unwrap_or_default()
+                    redact(&serde_json::to_string(&body).unwrap_or_default())
                 );
                 request = request.json(&body);
                 body_for_logging = Some(body);
@@ -240,13 +930,26 @@ impl ToolExecutor {
                 .map(|b| b.keys().collect::<Vec<_>>())
         );
 
-        // Execute request
-        let response = request
-            .send()
-            .await
+        let mut request = request
+            .build()
             .map_err(|e| WalletError::StorageError(format!("HTTP request failed: {}", e)))?;
 
+        // Sign the final request
+        if let Some(signer) = &auth.signer {
+            signer.sign(&mut request)?;
+        }
+
+        // Execute request
+        let response = client.execute(request).await.map_err(|e| {
+            if e.is_connect() || e.is_timeout() {
+                WalletError::ConnectionError(e.to_string())
+            } else {
+                WalletError::StorageError(format!("HTTP request failed: {}", e))
+            }
+        })?;
+
         let status = response.status();
+        let health = HealthOutcome::from_response(status.as_u16(), response.headers());
         let response_text = response
             .text()
             .await
@@ -254,20 +957,25 @@ impl ToolExecutor {
 
         debug!("Response status: {}", status);
 
-        // Parse response
+        Ok((status, health, response_text))
+    }
+
+    /// Convert an HTTP response into a tool result
+    fn format_response(status: StatusCode, response_text: String) -> ToolCallResult {
         if status.is_success() {
             // Try to format JSON response
             let formatted = match serde_json::from_str::<Value>(&response_text) {
                 Ok(json) => serde_json::to_string_pretty(&json).unwrap_or(response_text),
                 Err(_) => response_text,
             };
-            Ok(ToolCallResult::text(formatted))
+            ToolCallResult::text(formatted)
         } else {
-            error!("Request failed with status {}: {}", status, response_text);
-            Ok(ToolCallResult::error(format!(
-                "HTTP {} - {}",
-                status, response_text
-            )))
+            error!(
+                "Request failed with status {}: {}",
+                status,
+                redact(&response_text)
+            );
+            ToolCallResult::error(format!("HTTP {} - {}", status, response_text))
         }
     }
 }
@@ -275,20 +983,1245 @@ impl ToolExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::ToolContent;
+    use crate::tools::ToolGenerator;
+    use axum::{extract::Query, http::HeaderMap, routing::get, routing::post, Json, Router};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+    use wallet_core::{CredentialBinding, EncryptedFileStorage, ExternalSource, IntegrationStatus};
+
+    /// Serve `app` on a local port, returning its base URL
+    async fn serve(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        base
+    }
+
+    /// Initialize a wallet in a temporary directory
+    async fn empty_wallet() -> (TempDir, Arc<RwLock<Wallet>>) {
+        let temp_dir = TempDir::new().unwrap();
+        let storage =
+            Arc::new(EncryptedFileStorage::with_dir(temp_dir.path().to_path_buf()).unwrap());
+        let mut wallet = Wallet::with_storage(storage);
+        wallet.initialize("test-password").await.unwrap();
+        (temp_dir, Arc::new(RwLock::new(wallet)))
+    }
+
+    /// Initialize a wallet with a `test` integration for the API at `base`
+    ///
+    /// `spec` is the part of the spec after `servers`; `{base}` in it is
+    /// replaced with the API's URL.
+    async fn test_wallet(base: &str, spec: &str) -> (TempDir, Arc<RwLock<Wallet>>) {
+        let (temp_dir, wallet) = empty_wallet().await;
+        let spec = format!(
+            "openapi: \"3.0.0\"\ninfo:\n  title: Test API\n  version: \"1.0.0\"\nservers:\n  - url: {}\n{}",
+            base,
+            spec.replace("{base}", base)
+        );
+        wallet
+            .read()
+            .await
+            .integrations
+            .add_from_content("test", &spec)
+            .await
+            .unwrap();
+        (temp_dir, wallet)
+    }
+
+    /// Serve `app` and initialize a wallet with a `test` integration for it
+    async fn spawn_api(app: Router, spec: &str) -> (TempDir, Arc<RwLock<Wallet>>, String) {
+        let base = serve(app).await;
+        let (temp_dir, wallet) = test_wallet(&base, spec).await;
+        (temp_dir, wallet, base)
+    }
+
+    /// Make a credential the default of the `test` integration
+    async fn use_credential(wallet: &Arc<RwLock<Wallet>>, credential_id: Uuid) {
+        wallet
+            .read()
+            .await
+            .integrations
+            .set_credential("test", credential_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_refreshed_token_on_401() {
+        async fn users(headers: HeaderMap) -> (axum::http::StatusCode, Json<Value>) {
+            match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+                Some("Bearer at-fresh") => (
+                    axum::http::StatusCode::OK,
+                    Json(serde_json::json!([{"id": 1}])),
+                ),
+                _ => (
+                    axum::http::StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({"error": "expired"})),
+                ),
+            }
+        }
+
+        async fn token() -> Json<Value> {
+            Json(serde_json::json!({"access_token": "at-fresh", "expires_in": 3600}))
+        }
+
+        let app = Router::new()
+            .route("/users", get(users))
+            .route("/token", post(token));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        '200':
+          description: OK
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        authorizationCode:
+          authorizationUrl: {base}/authorize
+          tokenUrl: {base}/token
+          scopes: {}
+"#,
+        )
+        .await;
+        // No expiry recorded, so only the 401 can trigger the refresh
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_oauth2_token("test", "Test OAuth", "at-stale", Some("rt"), None)
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let executor = ToolExecutor::new(wallet);
+        let result = executor.execute("test_list_users", None).await.unwrap();
+
+        assert_eq!(result.is_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_basic_auth_encoded_at_request_time() {
+        async fn users(headers: HeaderMap) -> (axum::http::StatusCode, Json<Value>) {
+            match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+                Some("Basic YWxpY2U6aHVudGVyMg==") => (
+                    axum::http::StatusCode::OK,
+                    Json(serde_json::json!([{"id": 1}])),
+                ),
+                _ => (
+                    axum::http::StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({"error": "bad credentials"})),
+                ),
+            }
+        }
+
+        let app = Router::new().route("/users", get(users));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        '200':
+          description: OK
+components:
+  securitySchemes:
+    basic:
+      type: http
+      scheme: basic
+"#,
+        )
+        .await;
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_basic_auth("test", "Test Basic", "alice", "hunter2")
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let executor = ToolExecutor::new(wallet);
+        let result = executor.execute("test_list_users", None).await.unwrap();
+
+        assert_eq!(result.is_error, None);
+
+        let wallet = executor.wallet.read().await;
+        let usage = wallet
+            .credentials
+            .get(cred.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .usage;
+        assert_eq!(usage.use_count, 1);
+        assert_eq!(usage.error_count, 0);
+        assert_eq!(usage.last_integration.as_deref(), Some("test"));
+        assert_eq!(usage.last_operation.as_deref(), Some("list.users"));
+        assert_eq!(usage.last_status, Some(200));
+    }
+
+    #[tokio::test]
+    async fn test_hmac_signed_request() {
+        async fn orders(headers: HeaderMap, body: axum::body::Bytes) -> axum::http::StatusCode {
+            use hmac::{Hmac, Mac};
+
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+            let (Some(timestamp), Some(signature), Some("key-1")) = (
+                header("x-timestamp"),
+                header("x-signature"),
+                header("x-key-id"),
+            ) else {
+                return axum::http::StatusCode::UNAUTHORIZED;
+            };
+
+            let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"shh").unwrap();
+            mac.update(format!("{}\nPOST\n/orders\n", timestamp).as_bytes());
+            mac.update(&body);
+            if hex::encode(mac.finalize().into_bytes()) == signature {
+                axum::http::StatusCode::OK
+            } else {
+                axum::http::StatusCode::UNAUTHORIZED
+            }
+        }
+
+        let app = Router::new().route("/orders", post(orders));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /orders:
+    post:
+      operationId: createOrder
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .await;
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_multi_field(
+                "test",
+                "Test HMAC",
+                &[("secret", "shh"), ("key_id", "key-1")],
+            )
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+        wallet
+            .read()
+            .await
+            .integrations
+            .set_signing(
+                "test",
+                Some(SigningConfig::HmacSha256 {
+                    signature_header: "X-Signature".to_string(),
+                    timestamp_header: "X-Timestamp".to_string(),
+                    key_id_header: Some("X-Key-Id".to_string()),
+                }),
+            )
+            .await
+            .unwrap();
 
-    #[test]
-    fn test_parse_tool_name() {
-        let wallet = Arc::new(RwLock::new(Wallet::new().expect("Failed to create wallet")));
         let executor = ToolExecutor::new(wallet);
+        let result = executor
+            .execute("test_create_order", Some(serde_json::json!({"sku": "abc"})))
+            .await
+            .unwrap();
+
+        assert_eq!(result.is_error, None);
+    }
+
+    /// Serve one canned JSON response per connection over TLS that requires a client certificate
+    async fn spawn_mtls_server() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::rustls::pki_types::pem::PemObject;
+        use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+        use tokio_rustls::rustls::server::WebPkiClientVerifier;
+        use tokio_rustls::rustls::{crypto, RootCertStore, ServerConfig};
+
+        let provider = Arc::new(crypto::ring::default_provider());
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(
+                CertificateDer::from_pem_slice(include_bytes!("../../testdata/mtls/ca.pem"))
+                    .unwrap(),
+            )
+            .unwrap();
+        let verifier = WebPkiClientVerifier::builder_with_provider(roots.into(), provider.clone())
+            .build()
+            .unwrap();
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(
+                vec![CertificateDer::from_pem_slice(include_bytes!(
+                    "../../testdata/mtls/server.pem"
+                ))
+                .unwrap()],
+                PrivateKeyDer::from_pem_slice(include_bytes!("../../testdata/mtls/server.key"))
+                    .unwrap(),
+            )
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!(
+            "https://localhost:{}",
+            listener.local_addr().unwrap().port()
+        );
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    continue;
+                };
+
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let body = r#"{"status":"ok"}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+                stream.shutdown().await.ok();
+            }
+        });
+
+        base
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate_presented() {
+        let base = spawn_mtls_server().await;
+        let ca_pem = include_str!("../../testdata/mtls/ca.pem");
+
+        // The server refuses clients without a certificate
+        let anonymous = Client::builder()
+            .add_root_certificate(Certificate::from_pem(ca_pem.as_bytes()).unwrap())
+            .build()
+            .unwrap();
+        assert!(anonymous
+            .get(format!("{}/health", base))
+            .send()
+            .await
+            .is_err());
+
+        let (_temp_dir, wallet) = test_wallet(
+            &base,
+            r#"
+paths:
+  /health:
+    get:
+      operationId: getHealth
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .await;
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_client_certificate(
+                "test",
+                "Mesh Identity",
+                include_str!("../../testdata/mtls/client.pem"),
+                include_str!("../../testdata/mtls/client.key"),
+                Some(ca_pem),
+            )
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let executor = ToolExecutor::new(wallet.clone());
+        let result = executor.execute("test_get_health", None).await.unwrap();
+        assert_eq!(result.is_error, None);
+
+        // The client is built once and reused
+        let result = executor.execute("test_get_health", None).await.unwrap();
+        assert_eq!(result.is_error, None);
+        assert_eq!(executor.mtls_clients.read().await.len(), 1);
+
+        // A rotated certificate is presented on the next call
+        let rotate = |certificate: &'static str, key: &'static str| {
+            let wallet = wallet.clone();
+            async move {
+                let wallet = wallet.read().await;
+                wallet
+                    .credentials
+                    .update_field(cred.id, "certificate", certificate)
+                    .await
+                    .unwrap();
+                wallet.credentials.update_value(cred.id, key).await.unwrap();
+            }
+        };
+        rotate(
+            include_str!("../../testdata/mtls/other.pem"),
+            include_str!("../../testdata/mtls/other.key"),
+        )
+        .await;
+        assert!(executor.execute("test_get_health", None).await.is_err());
+
+        rotate(
+            include_str!("../../testdata/mtls/client.pem"),
+            include_str!("../../testdata/mtls/client.key"),
+        )
+        .await;
+        let result = executor.execute("test_get_health", None).await.unwrap();
+        assert_eq!(result.is_error, None);
+        assert_eq!(executor.mtls_clients.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_multi_field_credential_placement() {
+        async fn metrics(
+            headers: HeaderMap,
+            Query(query): Query<HashMap<String, String>>,
+        ) -> (axum::http::StatusCode, Json<Value>) {
+            let header = headers.get("dd-api-key").and_then(|v| v.to_str().ok());
+            let cookie = headers.get("cookie").and_then(|v| v.to_str().ok());
+            if header == Some("api-secret")
+                && query.get("application_key").map(String::as_str) == Some("app-secret")
+                && cookie == Some("session=cookie-secret")
+            {
+                (axum::http::StatusCode::OK, Json(serde_json::json!([])))
+            } else {
+                (
+                    axum::http::StatusCode::FORBIDDEN,
+                    Json(serde_json::json!({"error": "missing keys"})),
+                )
+            }
+        }
+
+        let app = Router::new().route("/metrics", get(metrics));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /metrics:
+    get:
+      operationId: listMetrics
+      responses:
+        '200':
+          description: OK
+components:
+  securitySchemes:
+    apiKeyAuth:
+      type: apiKey
+      in: header
+      name: DD-API-KEY
+    appKeyAuth:
+      type: apiKey
+      in: query
+      name: application_key
+    session:
+      type: apiKey
+      in: cookie
+      name: session
+"#,
+        )
+        .await;
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_multi_field(
+                "test",
+                "Test Keys",
+                &[
+                    ("api_key", "api-secret"),
+                    ("app_key", "app-secret"),
+                    ("session", "cookie-secret"),
+                ],
+            )
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+        // "session" matches its scheme by name; the others are mapped explicitly
+        for (field, scheme) in [("api_key", "apiKeyAuth"), ("app_key", "appKeyAuth")] {
+            wallet
+                .read()
+                .await
+                .integrations
+                .set_credential_field("test", field, Some(scheme))
+                .await
+                .unwrap();
+        }
+
+        let executor = ToolExecutor::new(wallet);
+        let result = executor.execute("test_list_metrics", None).await.unwrap();
+
+        assert_eq!(result.is_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_placed_per_auth_scheme() {
+        async fn items(
+            headers: HeaderMap,
+            Query(query): Query<HashMap<String, String>>,
+        ) -> (axum::http::StatusCode, Json<Value>) {
+            let found = headers
+                .get("x-api-key")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| format!("header:{}", v))
+                .or_else(|| query.get("api_key").map(|v| format!("query:{}", v)))
+                .or_else(|| {
+                    headers
+                        .get("cookie")
+                        .and_then(|v| v.to_str().ok())
+                        .map(|v| format!("cookie:{}", v))
+                });
+            let bearer = headers.contains_key("authorization");
+            (
+                axum::http::StatusCode::OK,
+                Json(serde_json::json!({"found": found, "authorization": bearer})),
+            )
+        }
+
+        let app = Router::new().route("/items", get(items));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /items:
+    get:
+      operationId: listItems
+      responses:
+        '200':
+          description: OK
+security:
+  - apiKey: []
+components:
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-API-Key
+"#,
+        )
+        .await;
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_api_key("test", "Test Key", "k-123")
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let executor = ToolExecutor::new(wallet.clone());
+        let text = |result: ToolCallResult| serde_json::to_string(&result).unwrap();
+
+        let result = text(executor.execute("test_list_items", None).await.unwrap());
+        assert!(result.contains("header:k-123"));
+        assert!(result.contains(r#"\"authorization\": false"#));
+
+        for (location, expected) in [
+            (ApiKeyLocation::Query, "query:k-123"),
+            (ApiKeyLocation::Cookie, "cookie:sid=k-123"),
+        ] {
+            let name = if location == ApiKeyLocation::Query {
+                "api_key"
+            } else {
+                "sid"
+            };
+            wallet
+                .read()
+                .await
+                .integrations
+                .set_auth_override(
+                    "test",
+                    Some(AuthScheme::ApiKey {
+                        name: name.to_string(),
+                        location,
+                    }),
+                )
+                .await
+                .unwrap();
+
+            let result = text(executor.execute("test_list_items", None).await.unwrap());
+            assert!(result.contains(expected), "{}", result);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_operation_security() {
+        async fn echo(
+            headers: HeaderMap,
+            Query(query): Query<HashMap<String, String>>,
+        ) -> Json<Value> {
+            Json(serde_json::json!({
+                "authorization": headers.get("authorization").and_then(|v| v.to_str().ok()),
+                "x_api_key": headers.get("x-api-key").and_then(|v| v.to_str().ok()),
+                "query_key": query.get("key"),
+            }))
+        }
+
+        let app = Router::new()
+            .route("/health", get(echo))
+            .route("/items", get(echo))
+            .route("/legacy", get(echo))
+            .route("/reports", get(echo))
+            .route("/admin", get(echo));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /health:
+    get:
+      operationId: health
+      security: []
+      responses:
+        '200':
+          description: OK
+  /items:
+    get:
+      operationId: listItems
+      responses:
+        '200':
+          description: OK
+  /legacy:
+    get:
+      operationId: listLegacy
+      security:
+        - queryKey: []
+      responses:
+        '200':
+          description: OK
+  /reports:
+    get:
+      operationId: listReports
+      security:
+        - oauth: [reports:read]
+        - queryKey: []
+      responses:
+        '200':
+          description: OK
+  /admin:
+    get:
+      operationId: admin
+      security:
+        - basic: []
+      responses:
+        '200':
+          description: OK
+security:
+  - headerKey: []
+components:
+  securitySchemes:
+    headerKey:
+      type: apiKey
+      in: header
+      name: X-API-Key
+    queryKey:
+      type: apiKey
+      in: query
+      name: key
+    basic:
+      type: http
+      scheme: basic
+    oauth:
+      type: oauth2
+      flows:
+        clientCredentials:
+          tokenUrl: https://auth.example.com/token
+          scopes:
+            reports:read: Read reports
+"#,
+        )
+        .await;
 
-        let (key, path) = executor.parse_tool_name("stripe_customers_create").unwrap();
-        assert_eq!(key, "stripe");
-        assert_eq!(path, "customers.create");
+        let executor = ToolExecutor::new(wallet.clone());
+        let text = |result: ToolCallResult| serde_json::to_string(&result).unwrap();
 
-        let (key, path) = executor
-            .parse_tool_name("openai_chat_completions_create")
+        // Public operations work before any credential is bound
+        let result = text(executor.execute("test_health", None).await.unwrap());
+        assert!(result.contains(r#"\"x_api_key\": null"#), "{}", result);
+        assert!(matches!(
+            executor.execute("test_list_items", None).await,
+            Err(WalletError::CredentialNotFound(_))
+        ));
+
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_api_key("test", "Test Key", "k-123")
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let result = text(executor.execute("test_health", None).await.unwrap());
+        assert!(result.contains(r#"\"x_api_key\": null"#), "{}", result);
+
+        let result = text(executor.execute("test_list_items", None).await.unwrap());
+        assert!(result.contains(r#"\"x_api_key\": \"k-123\""#), "{}", result);
+
+        let result = text(executor.execute("test_list_legacy", None).await.unwrap());
+        assert!(result.contains(r#"\"query_key\": \"k-123\""#), "{}", result);
+        assert!(result.contains(r#"\"x_api_key\": null"#), "{}", result);
+
+        // The alternative made for API keys is used
+        let result = text(executor.execute("test_list_reports", None).await.unwrap());
+        assert!(result.contains(r#"\"query_key\": \"k-123\""#), "{}", result);
+
+        // Operations that accept none of the credential's schemes are not called
+        assert!(matches!(
+            executor.execute("test_admin", None).await,
+            Err(WalletError::InvalidCredential(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_oauth2_scope_rejected() {
+        let (_temp_dir, wallet) = test_wallet(
+            "http://127.0.0.1:9",
+            r#"
+paths:
+  /users:
+    delete:
+      operationId: deleteUsers
+      security:
+        - oauth: [users:write]
+        - oauth: [users:admin]
+      responses:
+        '204':
+          description: Deleted
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        authorizationCode:
+          authorizationUrl: https://auth.example.com/authorize
+          tokenUrl: https://auth.example.com/token
+          scopes:
+            users:write: Modify users
+"#,
+        )
+        .await;
+        {
+            let wallet = wallet.read().await;
+            let cred = wallet
+                .credentials
+                .add_oauth2_token("test", "Test OAuth", "at", None, None)
+                .await
+                .unwrap();
+            wallet
+                .credentials
+                .set_granted_scopes(cred.id, Some(vec!["users:read".to_string()]))
+                .await
+                .unwrap();
+            wallet
+                .integrations
+                .set_credential("test", cred.id)
+                .await
+                .unwrap();
+        }
+
+        let executor = ToolExecutor::new(wallet);
+        let result = executor.execute("test_delete_users", None).await;
+
+        assert!(matches!(result, Err(WalletError::InsufficientScope(s)) if s == "users:write"));
+    }
+
+    #[tokio::test]
+    async fn test_credential_selected_by_label() {
+        async fn whoami(headers: HeaderMap) -> Json<Value> {
+            Json(serde_json::json!({
+                "key": headers.get("authorization").and_then(|v| v.to_str().ok()),
+            }))
+        }
+        async fn sandbox(headers: HeaderMap) -> Json<Value> {
+            Json(serde_json::json!({
+                "sandbox": headers.get("authorization").and_then(|v| v.to_str().ok()),
+            }))
+        }
+
+        let app = Router::new()
+            .route("/whoami", get(whoami))
+            .route("/sandbox/whoami", get(sandbox));
+        let (_temp_dir, wallet, base) = spawn_api(
+            app,
+            r#"
+paths:
+  /whoami:
+    get:
+      operationId: whoami
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .await;
+        {
+            let wallet = wallet.read().await;
+            let live = wallet
+                .credentials
+                .add_api_key("test", "Live", "sk_live")
+                .await
+                .unwrap();
+            let sandbox_key = wallet
+                .credentials
+                .add_api_key("test", "Test", "sk_test")
+                .await
+                .unwrap();
+            wallet
+                .integrations
+                .bind_credential("test", CredentialBinding::new("live", live.id), true)
+                .await
+                .unwrap();
+            let mut binding = CredentialBinding::new("test", sandbox_key.id);
+            binding.server_url = Some(format!("{}/sandbox", base));
+            wallet
+                .integrations
+                .bind_credential("test", binding, false)
+                .await
+                .unwrap();
+        }
+
+        let executor = ToolExecutor::new(wallet);
+        let text = |result: ToolCallResult| serde_json::to_string(&result).unwrap();
+
+        let result = text(executor.execute("test_whoami", None).await.unwrap());
+        assert!(
+            result.contains(r#"\"key\": \"Bearer sk_live\""#),
+            "{}",
+            result
+        );
+
+        let args = serde_json::json!({ CREDENTIAL_ARGUMENT: "test" });
+        let result = text(executor.execute("test_whoami", Some(args)).await.unwrap());
+        assert!(
+            result.contains(r#"\"sandbox\": \"Bearer sk_test\""#),
+            "{}",
+            result
+        );
+
+        let args = serde_json::json!({ CREDENTIAL_ARGUMENT: "staging" });
+        assert!(matches!(
+            executor.execute("test_whoami", Some(args)).await,
+            Err(WalletError::CredentialNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validate_credential() {
+        async fn me(headers: HeaderMap) -> axum::response::Response {
+            use axum::response::IntoResponse;
+
+            match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+                Some("Bearer good") => Json(serde_json::json!({"login": "alice"})).into_response(),
+                Some("Bearer limited") => (
+                    axum::http::StatusCode::FORBIDDEN,
+                    [("x-ratelimit-remaining", "0")],
+                    Json(serde_json::json!({"error": "rate limit exceeded"})),
+                )
+                    .into_response(),
+                _ => (
+                    axum::http::StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({"error": "bad credentials"})),
+                )
+                    .into_response(),
+            }
+        }
+
+        let app = Router::new().route("/me", get(me));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /me:
+    get:
+      operationId: getMe
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .await;
+        {
+            let wallet = wallet.read().await;
+            for (label, name, make_default) in [
+                ("good", "Good", true),
+                ("revoked", "Revoked", false),
+                ("limited", "Limited", false),
+            ] {
+                let cred = wallet
+                    .credentials
+                    .add_api_key("test", name, label)
+                    .await
+                    .unwrap();
+                wallet
+                    .integrations
+                    .bind_credential("test", CredentialBinding::new(label, cred.id), make_default)
+                    .await
+                    .unwrap();
+            }
+            wallet
+                .integrations
+                .set_health_check_interval("test", Some(3600))
+                .await
+                .unwrap();
+        }
+
+        let executor = ToolExecutor::new(wallet);
+
+        let integration = executor.validate_credential("test", None).await.unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Active);
+        assert!(integration.last_validated_at.is_some());
+
+        // A rate limited probe says nothing about the credential
+        let integration = executor
+            .validate_credential("test", Some("limited"))
+            .await
+            .unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Active);
+
+        // A probe that cannot be sent still schedules the next check
+        let integration = executor
+            .validate_credential("test", Some("missing"))
+            .await
+            .unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        assert!(integration.next_health_check_at.unwrap() > chrono::Utc::now());
+
+        let integration = executor
+            .validate_credential("test", Some("revoked"))
+            .await
+            .unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        assert!(integration.error.unwrap().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_reflected_secret_redacted() {
+        async fn me(headers: HeaderMap) -> (axum::http::StatusCode, Json<Value>) {
+            let authorization = headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            (
+                axum::http::StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({
+                    "error": format!("invalid key {}", authorization.trim_start_matches("Bearer ")),
+                    "header": authorization,
+                })),
+            )
+        }
+
+        let app = Router::new().route("/me", get(me));
+        let (_temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /me:
+    get:
+      operationId: getMe
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .await;
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_api_key("test", "Reflected", "reflected-secret-9f8e7d")
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let executor = ToolExecutor::new(wallet);
+        let result = executor.execute("test_get_me", None).await.unwrap();
+
+        assert_eq!(result.is_error, Some(true));
+        let ToolContent::Text { text } = &result.content[0] else {
+            panic!("expected text content");
+        };
+        assert!(text.contains("invalid key [REDACTED]"));
+        assert!(!text.contains("reflected-secret-9f8e7d"));
+    }
+
+    #[tokio::test]
+    async fn test_rotated_external_secret_resolved_on_401() {
+        async fn me(headers: HeaderMap) -> axum::http::StatusCode {
+            match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+                Some("Bearer rotated-2") => axum::http::StatusCode::OK,
+                _ => axum::http::StatusCode::UNAUTHORIZED,
+            }
+        }
+
+        let app = Router::new().route("/me", get(me));
+        let (temp_dir, wallet, _) = spawn_api(
+            app,
+            r#"
+paths:
+  /me:
+    get:
+      operationId: getMe
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .await;
+
+        // Prints "rotated-N" on its N-th run, as if the key rotated in between
+        let counter = temp_dir.path().join("resolved");
+        let source = ExternalSource::Command {
+            program: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!(
+                    "echo x >> '{0}'; echo \"rotated-$(wc -l < '{0}' | tr -d ' ')\"",
+                    counter.display()
+                ),
+            ],
+        };
+        let cred = wallet
+            .read()
+            .await
+            .credentials
+            .add_external("test", "Central", source, 300, None)
+            .await
+            .unwrap();
+        use_credential(&wallet, cred.id).await;
+
+        let executor = ToolExecutor::new(wallet);
+        let result = executor.execute("test_get_me", None).await.unwrap();
+        assert_eq!(result.is_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_filtered_operation_cannot_be_called() {
+        let (_temp_dir, wallet) = test_wallet(
+            "https://api.test.com",
+            r#"
+paths:
+  /users/{id}:
+    delete:
+      operationId: deleteUser
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        '204':
+          description: Deleted
+"#,
+        )
+        .await;
+        wallet
+            .read()
+            .await
+            .integrations
+            .set_operation_filter("test", wallet_core::OperationFilter::read_only())
+            .await
+            .unwrap();
+
+        let executor = ToolExecutor::new(wallet);
+        let err = executor
+            .execute("test_delete_user", Some(serde_json::json!({"id": "1"})))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WalletError::OperationNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_colliding_tool_names_call_their_operations() {
+        let app = Router::new()
+            .route("/users", get(|| async { "users" }))
+            .route("/users/all", get(|| async { "all users" }));
+        let base = serve(app).await;
+
+        let spec = format!(
+            r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+servers:
+  - url: {}
+paths:
+  /users:
+    get:
+      operationId: list_users
+      security: []
+      responses:
+        '200':
+          description: OK
+  /users/all:
+    get:
+      operationId: listUsers
+      security: []
+      responses:
+        '200':
+          description: OK
+"#,
+            base
+        );
+
+        let (_temp_dir, wallet) = empty_wallet().await;
+        {
+            let wallet = wallet.read().await;
+            assert!(matches!(
+                wallet.integrations.add_from_content("my_api", &spec).await,
+                Err(WalletError::InvalidIntegrationKey(_))
+            ));
+            let integration = wallet
+                .integrations
+                .add_from_content("my-api", &spec)
+                .await
+                .unwrap();
+            assert_eq!(integration.tool_name_collisions, ["my-api_list_users_2"]);
+
+            let stored = wallet.integrations.get_stored("my-api").await.unwrap();
+            let names: Vec<String> = ToolGenerator::new()
+                .generate_tools(&stored)
+                .into_iter()
+                .map(|tool| tool.name)
+                .collect();
+            assert_eq!(names, ["my-api_list_users", "my-api_list_users_2"]);
+        }
+
+        let executor = ToolExecutor::new(wallet);
+        let text = |result: ToolCallResult| serde_json::to_string(&result).unwrap();
+        let result = text(executor.execute("my-api_list_users", None).await.unwrap());
+        assert!(result.contains("\"users\""), "{}", result);
+        let result = text(executor.execute("my-api_list_users_2", None).await.unwrap());
+        assert!(result.contains("all users"), "{}", result);
+        assert!(matches!(
+            executor.execute("my_api_list_users", None).await,
+            Err(WalletError::OperationNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_request_outcomes_update_status() {
+        async fn flaky() -> axum::http::StatusCode {
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        }
+
+        let base = serve(Router::new().route("/status", get(flaky))).await;
+
+        // Nothing listens on this port once the listener is dropped
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unreachable = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let spec = |key: &str, server: &str| {
+            format!(
+                r#"
+openapi: "3.0.0"
+info:
+  title: {key}
+  version: "1.0.0"
+servers:
+  - url: {server}
+paths:
+  /status:
+    get:
+      operationId: getStatus
+      security: []
+      responses:
+        '200':
+          description: OK
+"#
+            )
+        };
+
+        let (_temp_dir, wallet) = empty_wallet().await;
+        {
+            let wallet = wallet.read().await;
+            wallet
+                .integrations
+                .add_from_content("flaky", &spec("flaky", &base))
+                .await
+                .unwrap();
+            wallet
+                .integrations
+                .add_from_content("down", &spec("down", &unreachable))
+                .await
+                .unwrap();
+        }
+
+        let executor = ToolExecutor::new(wallet);
+        let status = |key: &'static str| {
+            let executor = &executor;
+            async move {
+                executor
+                    .wallet
+                    .read()
+                    .await
+                    .integrations
+                    .get(key)
+                    .await
+                    .unwrap()
+                    .status
+            }
+        };
+
+        for attempt in 1..=wallet_core::SERVER_ERROR_THRESHOLD {
+            executor.execute("flaky_get_status", None).await.unwrap();
+            let expected = if attempt < wallet_core::SERVER_ERROR_THRESHOLD {
+                IntegrationStatus::Pending
+            } else {
+                IntegrationStatus::Error
+            };
+            assert_eq!(status("flaky").await, expected);
+        }
+
+        let err = executor.execute("down_get_status", None).await.unwrap_err();
+        assert!(matches!(err, WalletError::ConnectionError(_)));
+        assert_eq!(status("down").await, IntegrationStatus::Error);
+
+        // Disabled integrations cannot be called
+        executor
+            .wallet
+            .read()
+            .await
+            .integrations
+            .set_enabled("down", false)
+            .await
             .unwrap();
-        assert_eq!(key, "openai");
-        assert_eq!(path, "chat.completions.create");
+        let err = executor.execute("down_get_status", None).await.unwrap_err();
+        assert!(matches!(err, WalletError::IntegrationDisabled(_)));
+        assert_eq!(status("down").await, IntegrationStatus::Disabled);
     }
 }
@@ -1,9 +1,10 @@
 //! Generate MCP tools from OpenAPI operations
 
+use super::CREDENTIAL_ARGUMENT;
 use crate::protocol::{McpInputSchema, McpTool};
 use openapi_parser::{ApiOperation, ParameterLocation};
 use serde_json::{Map, Value};
-use wallet_core::StoredIntegration;
+use wallet_core::{tool_name, StoredIntegration};
 
 /// Generator for MCP tools from OpenAPI specs
 pub struct ToolGenerator;
@@ -37,29 +38,35 @@ impl ToolGenerator {
         Self
     }
 
-    /// Generate MCP tools from an integration
-    pub fn generate_tools(
-        &self,
-        integration_key: &str,
-        stored: &StoredIntegration,
-    ) -> Vec<McpTool> {
+    /// Generate MCP tools from an integration (operations hidden by its filter are skipped)
+    pub fn generate_tools(&self, stored: &StoredIntegration) -> Vec<McpTool> {
         stored
-            .operations
-            .iter()
-            .map(|op| self.generate_tool(integration_key, op))
+            .allowed_tools()
+            .map(|(name, op)| {
+                let mut tool = self.build_tool(name.to_string(), op);
+                self.add_credential_argument(&mut tool, stored);
+
+                // Show the OAuth2 scopes the operation needs
+                let scopes = stored.oauth2_scopes(op);
+                if let (Some(description), false) = (&mut tool.description, scopes.is_empty()) {
+                    description.push_str(&format!("\n\nRequired scopes: {}", scopes.join(", ")));
+                }
+
+                tool
+            })
             .collect()
     }
 
-    /// Generate a single MCP tool from an operation
+    /// Generate a single MCP tool from an operation, named `{integration}_{operation_id}`
+    ///
+    /// Within an integration, use [`Self::generate_tools`], which uses the
+    /// collision-free names the integration assigned.
     pub fn generate_tool(&self, integration_key: &str, operation: &ApiOperation) -> McpTool {
-        // Generate tool name: {integration}_{operation_id}
-        // Replace dots and other special chars with underscores
-        let tool_name = format!(
-            "{}_{}",
-            integration_key,
-            operation.normalized_id.replace('.', "_")
-        );
+        self.build_tool(tool_name(integration_key, operation), operation)
+    }
 
+    /// Build an MCP tool with the given name from an operation
+    fn build_tool(&self, tool_name: String, operation: &ApiOperation) -> McpTool {
         // Build description
         let description = self.build_description(operation);
 
@@ -73,6 +80,31 @@ impl ToolGenerator {
         }
     }
 
+    /// Let callers pick a bound credential by label
+    fn add_credential_argument(&self, tool: &mut McpTool, stored: &StoredIntegration) {
+        let bindings = &stored.integration.credential_bindings;
+        if bindings.is_empty() {
+            return;
+        }
+
+        let labels: Vec<Value> = bindings
+            .iter()
+            .map(|b| Value::String(b.label.clone()))
+            .collect();
+        let mut prop = Map::new();
+        prop.insert("type".to_string(), Value::String("string".to_string()));
+        prop.insert("enum".to_string(), Value::Array(labels));
+        prop.insert(
+            "description".to_string(),
+            Value::String("Label of the credential to use (omit for the default)".to_string()),
+        );
+
+        tool.input_schema
+            .properties
+            .get_or_insert_with(Map::new)
+            .insert(CREDENTIAL_ARGUMENT.to_string(), Value::Object(prop));
+    }
+
     /// Build tool description from operation
     fn build_description(&self, operation: &ApiOperation) -> String {
         let mut parts = Vec::new();
@@ -314,6 +346,7 @@ impl Default for ToolGenerator {
 mod tests {
     use super::*;
     use openapi_parser::{HttpMethod, OperationParameter};
+    use wallet_core::CredentialBinding;
 
     fn test_operation() -> ApiOperation {
         ApiOperation {
@@ -337,6 +370,7 @@ mod tests {
             request_body: None,
             responses: vec![],
             security: vec![],
+            public: false,
         }
     }
 
@@ -382,4 +416,110 @@ mod tests {
         let required = tool.input_schema.required.as_ref().unwrap();
         assert!(required.contains(&"email".to_string()));
     }
+
+    #[test]
+    fn test_generate_tools_shows_required_scopes() {
+        let spec = openapi_parser::OpenApiParser::parse_yaml(
+            r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      security:
+        - oauth: [users:read]
+      responses:
+        '200':
+          description: OK
+  /health:
+    get:
+      operationId: health
+      security: []
+      responses:
+        '200':
+          description: OK
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        clientCredentials:
+          tokenUrl: https://auth.example.com/token
+          scopes:
+            users:read: Read users
+"#,
+        )
+        .unwrap();
+        let stored = StoredIntegration::from_spec("test".to_string(), spec, None);
+
+        let tools = ToolGenerator::new().generate_tools(&stored);
+        let description = |name: &str| {
+            tools
+                .iter()
+                .find(|t| t.name == name)
+                .and_then(|t| t.description.clone())
+                .unwrap()
+        };
+
+        assert!(description("test_list_users").contains("Required scopes: users:read"));
+        assert!(!description("test_health").contains("Required scopes"));
+    }
+
+    #[test]
+    fn test_generate_tools_offers_credential_labels() {
+        let spec = openapi_parser::OpenApiParser::parse_yaml(
+            r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .unwrap();
+        let mut stored = StoredIntegration::from_spec("test".to_string(), spec, None);
+
+        let tools = ToolGenerator::new().generate_tools(&stored);
+        assert!(tools[0].input_schema.properties.is_none());
+
+        stored.integration.credential_bindings = vec![
+            CredentialBinding::new("live", uuid::Uuid::new_v4()),
+            CredentialBinding::new("test", uuid::Uuid::new_v4()),
+        ];
+        let tools = ToolGenerator::new().generate_tools(&stored);
+        let props = tools[0].input_schema.properties.as_ref().unwrap();
+        assert_eq!(
+            props[CREDENTIAL_ARGUMENT]["enum"],
+            serde_json::json!(["live", "test"])
+        );
+    }
+
+    #[test]
+    fn test_generate_tools_skips_filtered_operations() {
+        let spec = openapi_parser::ParsedSpec {
+            title: "Stripe".to_string(),
+            version: "1".to_string(),
+            description: None,
+            servers: vec![],
+            operations: vec![test_operation()],
+            security_schemes: Default::default(),
+            global_security: vec![],
+        };
+        let mut stored = StoredIntegration::from_spec("stripe".to_string(), spec, None);
+        let generator = ToolGenerator::new();
+
+        assert_eq!(generator.generate_tools(&stored).len(), 1);
+
+        stored.integration.operation_filter = wallet_core::OperationFilter::read_only();
+        assert!(generator.generate_tools(&stored).is_empty());
+    }
 }
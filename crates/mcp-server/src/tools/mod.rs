@@ -2,6 +2,11 @@
 
 mod executor;
 mod generator;
+mod signing;
 
 pub use executor::ToolExecutor;
 pub use generator::ToolGenerator;
+pub use signing::{AwsSigV4Signer, HmacSigner, RequestSigner};
+
+/// Reserved tool argument that selects a bound credential by label
+pub(crate) const CREDENTIAL_ARGUMENT: &str = "_credential";
@@ -0,0 +1,406 @@
+//! Request signers for APIs that sign the whole request
+//!
+//! Signers run after the URL, headers and body are final, just before the
+//! request is sent.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Request;
+use sha2::{Digest, Sha256};
+use wallet_core::WalletError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Characters AWS leaves unencoded (RFC 3986 unreserved)
+const AWS_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Signs an outgoing request
+pub trait RequestSigner: Send + Sync {
+    /// Sign the request as of `now`, adding headers in place
+    fn sign_at(&self, request: &mut Request, now: DateTime<Utc>) -> Result<(), WalletError>;
+
+    /// Sign the request with the current time
+    fn sign(&self, request: &mut Request) -> Result<(), WalletError> {
+        self.sign_at(request, Utc::now())
+    }
+}
+
+/// AWS Signature Version 4 signer
+pub struct AwsSigV4Signer {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    region: String,
+    service: String,
+}
+
+impl AwsSigV4Signer {
+    /// Create a new SigV4 signer
+    pub fn new(
+        access_key_id: &str,
+        secret_access_key: &str,
+        session_token: Option<&str>,
+        region: &str,
+        service: &str,
+    ) -> Self {
+        Self {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: session_token.map(String::from),
+            region: region.to_string(),
+            service: service.to_string(),
+        }
+    }
+
+    /// Build the canonical request (paths are double-encoded, as every AWS
+    /// service except S3 expects)
+    fn canonical_request(request: &Request, signed: &[(String, String)]) -> String {
+        let url = request.url();
+
+        let path = url
+            .path()
+            .split('/')
+            .map(|segment| utf8_percent_encode(segment, AWS_ENCODE_SET).to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut query: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| {
+                (
+                    utf8_percent_encode(&k, AWS_ENCODE_SET).to_string(),
+                    utf8_percent_encode(&v, AWS_ENCODE_SET).to_string(),
+                )
+            })
+            .collect();
+        query.sort();
+        let query = query
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let headers: String = signed
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let signed_names = Self::signed_header_names(signed);
+
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method(),
+            path,
+            query,
+            headers,
+            signed_names,
+            hex::encode(Sha256::digest(body_bytes(request)))
+        )
+    }
+
+    fn signed_header_names(signed: &[(String, String)]) -> String {
+        signed
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// Derive the signing key for a date
+    fn signing_key(&self, date: &str) -> Vec<u8> {
+        let key = hmac_sha256(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        );
+        let key = hmac_sha256(&key, self.region.as_bytes());
+        let key = hmac_sha256(&key, self.service.as_bytes());
+        hmac_sha256(&key, b"aws4_request")
+    }
+}
+
+impl RequestSigner for AwsSigV4Signer {
+    fn sign_at(&self, request: &mut Request, now: DateTime<Utc>) -> Result<(), WalletError> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        set_header(request, "x-amz-date", &amz_date)?;
+        if let Some(token) = &self.session_token {
+            set_header(request, "x-amz-security-token", token)?;
+        }
+
+        // The Host header is added by the HTTP client, so derive it from the URL
+        let url = request.url();
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let mut signed: Vec<(String, String)> = vec![("host".to_string(), host)];
+        for (name, value) in request.headers() {
+            let name = name.as_str();
+            if name == "content-type" || name.starts_with("x-amz-") {
+                let value = value.to_str().map_err(|_| {
+                    WalletError::ParseError(format!("Header {} is not valid text", name))
+                })?;
+                signed.push((name.to_string(), value.trim().to_string()));
+            }
+        }
+        signed.sort();
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(Self::canonical_request(request, &signed)))
+        );
+        let signature = hex::encode(hmac_sha256(
+            &self.signing_key(&date),
+            string_to_sign.as_bytes(),
+        ));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id,
+            scope,
+            Self::signed_header_names(&signed),
+            signature
+        );
+        set_header(request, "authorization", &authorization)
+    }
+}
+
+/// HMAC-SHA256 signer over timestamp, method, path and body
+///
+/// The signed message is `{timestamp}\n{METHOD}\n{path?query}\n{body}` and
+/// the signature is sent as lowercase hex.
+pub struct HmacSigner {
+    secret: String,
+    key_id: Option<String>,
+    signature_header: String,
+    timestamp_header: String,
+    key_id_header: Option<String>,
+}
+
+impl HmacSigner {
+    /// Create a new HMAC signer
+    pub fn new(secret: &str, signature_header: &str, timestamp_header: &str) -> Self {
+        Self {
+            secret: secret.to_string(),
+            key_id: None,
+            signature_header: signature_header.to_string(),
+            timestamp_header: timestamp_header.to_string(),
+            key_id_header: None,
+        }
+    }
+
+    /// Also send a key ID header
+    pub fn with_key_id(mut self, key_id: &str, header: &str) -> Self {
+        self.key_id = Some(key_id.to_string());
+        self.key_id_header = Some(header.to_string());
+        self
+    }
+
+    /// Build the message that is signed
+    fn message(request: &Request, timestamp: i64) -> Vec<u8> {
+        let url = request.url();
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+
+        let mut message = format!("{}\n{}\n{}\n", timestamp, request.method(), target).into_bytes();
+        message.extend_from_slice(body_bytes(request));
+        message
+    }
+}
+
+impl RequestSigner for HmacSigner {
+    fn sign_at(&self, request: &mut Request, now: DateTime<Utc>) -> Result<(), WalletError> {
+        let timestamp = now.timestamp();
+        let signature = hex::encode(hmac_sha256(
+            self.secret.as_bytes(),
+            &Self::message(request, timestamp),
+        ));
+
+        set_header(request, &self.timestamp_header, &timestamp.to_string())?;
+        set_header(request, &self.signature_header, &signature)?;
+        if let (Some(key_id), Some(header)) = (&self.key_id, &self.key_id_header) {
+            set_header(request, header, key_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute HMAC-SHA256
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Get the request body bytes (empty when there is no body)
+fn body_bytes(request: &Request) -> &[u8] {
+    request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .unwrap_or_default()
+}
+
+/// Insert a header, replacing any existing value
+fn set_header(request: &mut Request, name: &str, value: &str) -> Result<(), WalletError> {
+    let name = HeaderName::from_bytes(name.as_bytes())
+        .map_err(|e| WalletError::ParseError(format!("Invalid header name {}: {}", name, e)))?;
+    let value = HeaderValue::from_str(value)
+        .map_err(|e| WalletError::ParseError(format!("Invalid header value: {}", e)))?;
+    request.headers_mut().insert(name, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Credentials and expected signatures from the AWS SigV4 test suite
+    const AWS_ACCESS_KEY: &str = "AKIDEXAMPLE";
+    const AWS_SECRET_KEY: &str = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+
+    fn aws_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap()
+    }
+
+    fn request(method: reqwest::Method, url: &str) -> Request {
+        reqwest::Client::new().request(method, url).build().unwrap()
+    }
+
+    fn authorization(request: &Request) -> &str {
+        request.headers()["authorization"].to_str().unwrap()
+    }
+
+    #[test]
+    fn test_sigv4_get_vanilla() {
+        let signer =
+            AwsSigV4Signer::new(AWS_ACCESS_KEY, AWS_SECRET_KEY, None, "us-east-1", "service");
+        let mut req = request(reqwest::Method::GET, "https://example.amazonaws.com/");
+
+        signer.sign_at(&mut req, aws_time()).unwrap();
+
+        assert_eq!(req.headers()["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            authorization(&req),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn test_sigv4_get_vanilla_query_order() {
+        let signer =
+            AwsSigV4Signer::new(AWS_ACCESS_KEY, AWS_SECRET_KEY, None, "us-east-1", "service");
+        let mut req = request(
+            reqwest::Method::GET,
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+        );
+
+        signer.sign_at(&mut req, aws_time()).unwrap();
+
+        assert!(authorization(&req).ends_with(
+            "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ));
+    }
+
+    #[test]
+    fn test_sigv4_post_vanilla() {
+        let signer =
+            AwsSigV4Signer::new(AWS_ACCESS_KEY, AWS_SECRET_KEY, None, "us-east-1", "service");
+        let mut req = request(reqwest::Method::POST, "https://example.amazonaws.com/");
+
+        signer.sign_at(&mut req, aws_time()).unwrap();
+
+        assert!(authorization(&req).ends_with(
+            "Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        ));
+    }
+
+    #[test]
+    fn test_sigv4_iam_list_users() {
+        // Example request from the AWS SigV4 documentation
+        let signer = AwsSigV4Signer::new(AWS_ACCESS_KEY, AWS_SECRET_KEY, None, "us-east-1", "iam");
+        let mut req = reqwest::Client::new()
+            .get("https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08")
+            .header(
+                "content-type",
+                "application/x-www-form-urlencoded; charset=utf-8",
+            )
+            .build()
+            .unwrap();
+
+        signer.sign_at(&mut req, aws_time()).unwrap();
+
+        assert_eq!(
+            authorization(&req),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[test]
+    fn test_sigv4_session_token_signed() {
+        let signer = AwsSigV4Signer::new(
+            AWS_ACCESS_KEY,
+            AWS_SECRET_KEY,
+            Some("session"),
+            "us-east-1",
+            "service",
+        );
+        let mut req = request(reqwest::Method::GET, "https://example.amazonaws.com/");
+
+        signer.sign_at(&mut req, aws_time()).unwrap();
+
+        assert_eq!(req.headers()["x-amz-security-token"], "session");
+        assert!(authorization(&req).contains("SignedHeaders=host;x-amz-date;x-amz-security-token"));
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        // RFC 4231 test cases 1 and 2
+        assert_eq!(
+            hex::encode(hmac_sha256(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_hmac_signer() {
+        let signer = HmacSigner::new("secret", "X-Signature", "X-Timestamp")
+            .with_key_id("key-1", "X-Key-Id");
+        let mut req = reqwest::Client::new()
+            .post("https://api.example.com/v1/orders?dry_run=true")
+            .body(r#"{"amount":100}"#)
+            .build()
+            .unwrap();
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        signer.sign_at(&mut req, now).unwrap();
+
+        assert_eq!(req.headers()["x-timestamp"], "1700000000");
+        assert_eq!(req.headers()["x-key-id"], "key-1");
+        // HMAC-SHA256("secret", "1700000000\nPOST\n/v1/orders?dry_run=true\n{\"amount\":100}")
+        assert_eq!(
+            req.headers()["x-signature"],
+            "1d4f460a64e416bdbdf28ea20c3bc332dc704fea6ba353f457420ca701d34f21"
+        );
+    }
+}
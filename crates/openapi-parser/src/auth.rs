@@ -1,10 +1,12 @@
 //! Authentication scheme detection and handling
 
 use crate::types::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Detected authentication scheme for an API
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AuthScheme {
     /// No authentication required
     None,
@@ -38,11 +40,13 @@ impl AuthScheme {
             if security_schemes.is_empty() {
                 return AuthScheme::None;
             }
-            // Use the first defined scheme as default
-            if let Some((_, scheme)) = security_schemes.iter().next() {
-                return Self::from_scheme(scheme);
-            }
-            return AuthScheme::None;
+            // Use the first defined scheme (by name) as default
+            let mut names: Vec<&String> = security_schemes.keys().collect();
+            names.sort();
+            return match names.first() {
+                Some(name) => Self::from_scheme(&security_schemes[*name]),
+                None => AuthScheme::None,
+            };
         }
 
         // Collect all required auth schemes
@@ -132,7 +136,31 @@ impl AuthScheme {
         }
     }
 
+    /// Get the query parameter name for this auth scheme
+    pub fn query_param(&self) -> Option<&str> {
+        match self {
+            AuthScheme::ApiKey {
+                name,
+                location: ApiKeyLocation::Query,
+            } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Get the cookie name for this auth scheme
+    pub fn cookie_name(&self) -> Option<&str> {
+        match self {
+            AuthScheme::ApiKey {
+                name,
+                location: ApiKeyLocation::Cookie,
+            } => Some(name),
+            _ => None,
+        }
+    }
+
     /// Format the authorization header value
+    ///
+    /// Basic credentials must already be base64-encoded.
     pub fn format_header(&self, credential: &str) -> Option<String> {
         match self {
             AuthScheme::Bearer { .. } => Some(format!("Bearer {}", credential)),
@@ -168,6 +196,7 @@ mod tests {
         let requirements = vec![SecurityRequirement {
             scheme_name: "bearerAuth".to_string(),
             scopes: vec![],
+            alternative: 0,
         }];
 
         let auth = AuthScheme::detect(&schemes, &requirements);
@@ -194,6 +223,7 @@ mod tests {
         let requirements = vec![SecurityRequirement {
             scheme_name: "apiKey".to_string(),
             scopes: vec![],
+            alternative: 0,
         }];
 
         let auth = AuthScheme::detect(&schemes, &requirements);
@@ -213,4 +243,54 @@ mod tests {
         let header = auth.format_header("my-token");
         assert_eq!(header, Some("Bearer my-token".to_string()));
     }
+
+    #[test]
+    fn test_detect_without_requirements_is_stable() {
+        let mut schemes = HashMap::new();
+        for name in ["zeta", "alpha", "mid"] {
+            schemes.insert(
+                name.to_string(),
+                SecurityScheme::ApiKey {
+                    name: format!("X-{}", name),
+                    location: ApiKeyLocation::Header,
+                },
+            );
+        }
+
+        let auth = AuthScheme::detect(&schemes, &[]);
+        assert_eq!(auth.header_name(), Some("X-alpha"));
+    }
+
+    #[test]
+    fn test_api_key_placement() {
+        let query = AuthScheme::ApiKey {
+            name: "api_key".to_string(),
+            location: ApiKeyLocation::Query,
+        };
+        assert_eq!(query.header_name(), None);
+        assert_eq!(query.query_param(), Some("api_key"));
+        assert_eq!(query.cookie_name(), None);
+
+        let cookie = AuthScheme::ApiKey {
+            name: "session".to_string(),
+            location: ApiKeyLocation::Cookie,
+        };
+        assert_eq!(cookie.cookie_name(), Some("session"));
+        assert_eq!(cookie.format_header("x"), None);
+    }
+
+    #[test]
+    fn test_auth_scheme_roundtrip() {
+        let auth = AuthScheme::Multiple(vec![
+            AuthScheme::Basic,
+            AuthScheme::ApiKey {
+                name: "X-API-Key".to_string(),
+                location: ApiKeyLocation::Header,
+            },
+        ]);
+
+        let json = serde_json::to_string(&auth).unwrap();
+        let parsed: AuthScheme = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, auth);
+    }
 }
@@ -0,0 +1,385 @@
+//! Loading specs from local files, inlining `$ref`s to sibling files
+//!
+//! A spec split across files references the other files with relative
+//! `$ref`s (e.g., `./schemas/user.yaml#/User`). The bundler reads the main
+//! file and replaces those references with the referenced content, so the
+//! result is a single document the parser understands. References within
+//! the main file are left for the schema resolver. A schema that references
+//! itself is moved into `components/schemas` and referenced from there.
+
+use crate::error::{ParseError, ParseResult};
+use crate::parser::OpenApiParser;
+use crate::types::LocalSpec;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Inlines relative file references of a spec
+pub(crate) struct SpecBundler {
+    /// Files read so far, in the order they were first read
+    files: Vec<PathBuf>,
+    /// Parsed documents by path
+    documents: HashMap<PathBuf, Value>,
+    /// Component schema name of each referenced schema that has one
+    schema_names: HashMap<(PathBuf, String), String>,
+    /// Recursive schemas to add to the root's `components/schemas`
+    hoisted: Map<String, Value>,
+}
+
+impl SpecBundler {
+    /// Load a spec file and the files it references
+    pub(crate) fn load(path: &Path) -> ParseResult<LocalSpec> {
+        let path = canonicalize(path)?;
+        let mut bundler = Self {
+            files: Vec::new(),
+            documents: HashMap::new(),
+            schema_names: HashMap::new(),
+            hoisted: Map::new(),
+        };
+
+        let content = std::fs::read_to_string(&path)?;
+        let mut root = bundler.document(&path)?;
+        bundler.name_component_schemas(&root, &path);
+        bundler.inline(&mut root, &path, &path, &mut Vec::new())?;
+
+        if !bundler.hoisted.is_empty() {
+            let schemas = root
+                .as_object_mut()
+                .and_then(|root| {
+                    root.entry("components")
+                        .or_insert_with(|| Value::Object(Map::new()))
+                        .as_object_mut()
+                })
+                .and_then(|components| {
+                    components
+                        .entry("schemas")
+                        .or_insert_with(|| Value::Object(Map::new()))
+                        .as_object_mut()
+                })
+                .ok_or_else(|| {
+                    ParseError::InvalidFormat(format!(
+                        "components/schemas of {} is not an object",
+                        path.display()
+                    ))
+                })?;
+            schemas.append(&mut bundler.hoisted);
+        }
+
+        // Without external references the file is kept as written
+        let content = if bundler.files.len() == 1 {
+            content
+        } else {
+            debug!(
+                "Bundled {} with {} referenced files",
+                path.display(),
+                bundler.files.len() - 1
+            );
+            serde_json::to_string_pretty(&root)?
+        };
+
+        Ok(LocalSpec {
+            content,
+            path,
+            files: bundler.files,
+        })
+    }
+
+    /// Read and parse a file (once)
+    fn document(&mut self, path: &Path) -> ParseResult<Value> {
+        if let Some(document) = self.documents.get(path) {
+            return Ok(document.clone());
+        }
+
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ParseError::InvalidFormat(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let content = OpenApiParser::sanitize_large_numbers(&content);
+        let document: Value = if content.trim().starts_with('{') {
+            serde_json::from_str(&content)?
+        } else {
+            serde_yaml::from_str(&content)?
+        };
+
+        self.files.push(path.to_path_buf());
+        self.documents.insert(path.to_path_buf(), document.clone());
+        Ok(document)
+    }
+
+    /// Remember the root's component schemas that are references to other files
+    fn name_component_schemas(&mut self, root: &Value, path: &Path) {
+        let Some(schemas) = root
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+        else {
+            return;
+        };
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for (name, schema) in schemas {
+            let Some(reference) = schema.get("$ref").and_then(Value::as_str) else {
+                continue;
+            };
+            let (target, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+            if target.is_empty() || target.contains("://") {
+                continue;
+            }
+            if let Ok(target) = canonicalize(&dir.join(target)) {
+                self.schema_names
+                    .insert((target, pointer.to_string()), name.clone());
+            }
+        }
+    }
+
+    /// Component schema name for a recursive schema, adding it when new
+    fn schema_name(&mut self, key: &(PathBuf, String), root: &Path) -> String {
+        if let Some(name) = self.schema_names.get(key) {
+            return name.clone();
+        }
+
+        let (file, pointer) = key;
+        let base: String = pointer
+            .rsplit('/')
+            .next()
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .or_else(|| file.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "Schema".to_string())
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        let taken = |name: &str| {
+            self.schema_names.values().any(|n| n == name)
+                || self.documents[root]
+                    .pointer("/components/schemas")
+                    .and_then(Value::as_object)
+                    .is_some_and(|schemas| schemas.contains_key(name))
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while taken(&name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+
+        self.schema_names.insert(key.clone(), name.clone());
+        self.hoisted.insert(name.clone(), Value::Null);
+        name
+    }
+
+    /// Replace file references within `value`, which belongs to `file`
+    ///
+    /// `stack` holds the references being inlined; a reference back into it
+    /// (a recursive schema) points to the schema in `components/schemas`.
+    fn inline(
+        &mut self,
+        value: &mut Value,
+        file: &Path,
+        root: &Path,
+        stack: &mut Vec<(PathBuf, String)>,
+    ) -> ParseResult<()> {
+        match value {
+            Value::Object(map) => {
+                if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                    let (target, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+                    if target.contains("://") || (target.is_empty() && file == root) {
+                        return Ok(());
+                    }
+
+                    let target = if target.is_empty() {
+                        file.to_path_buf()
+                    } else {
+                        canonicalize(&file.parent().unwrap_or(Path::new(".")).join(target))?
+                    };
+                    let key = (target.clone(), pointer.to_string());
+                    if stack.contains(&key) {
+                        let name = self.schema_name(&key, root);
+                        *value = serde_json::json!({
+                            "$ref": format!("#/components/schemas/{}", name)
+                        });
+                        return Ok(());
+                    }
+
+                    let document = self.document(&target)?;
+                    let mut resolved = document.pointer(pointer).cloned().ok_or_else(|| {
+                        ParseError::InvalidFormat(format!("Unresolved $ref {}", reference))
+                    })?;
+
+                    stack.push(key);
+                    self.inline(&mut resolved, &target, root, stack)?;
+                    let key = stack.pop();
+
+                    // A recursive schema new to the components is added once
+                    let name = key.and_then(|key| self.schema_names.get(&key).cloned());
+                    if let Some(slot) = name.and_then(|name| self.hoisted.get_mut(&name)) {
+                        if slot.is_null() {
+                            *slot = resolved.clone();
+                        }
+                    }
+
+                    *value = resolved;
+                    return Ok(());
+                }
+
+                for nested in map.values_mut() {
+                    self.inline(nested, file, root, stack)?;
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.inline(item, file, root, stack)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Absolute path of a file, with a readable error when it is missing
+fn canonicalize(path: &Path) -> ParseResult<PathBuf> {
+    std::fs::canonicalize(path)
+        .map_err(|e| ParseError::InvalidFormat(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_sibling_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("schemas")).unwrap();
+        std::fs::write(
+            dir.path().join("openapi.yaml"),
+            r##"
+openapi: "3.0.0"
+info:
+  title: Local API
+  version: "1.0.0"
+paths:
+  /users:
+    $ref: "./paths/users.yaml"
+components:
+  schemas:
+    User:
+      $ref: "schemas/user.yaml#/User"
+"##,
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("paths")).unwrap();
+        std::fs::write(
+            dir.path().join("paths/users.yaml"),
+            r##"
+get:
+  operationId: listUsers
+  responses:
+    '200':
+      description: OK
+      content:
+        application/json:
+          schema:
+            $ref: "../schemas/user.yaml#/User"
+"##,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("schemas/user.yaml"),
+            r##"
+User:
+  type: object
+  properties:
+    name:
+      type: string
+    manager:
+      $ref: "#/User"
+"##,
+        )
+        .unwrap();
+
+        let local = SpecBundler::load(&dir.path().join("openapi.yaml")).unwrap();
+        assert_eq!(local.files.len(), 3);
+
+        let document: Value = serde_json::from_str(&local.content).unwrap();
+        let user = &document["components"]["schemas"]["User"];
+        assert_eq!(user["type"], "object");
+        // The recursive reference points to the component schema
+        assert_eq!(
+            user["properties"]["manager"]["$ref"],
+            "#/components/schemas/User"
+        );
+        let users = &document["paths"]["/users"]["get"]["responses"]["200"];
+        assert_eq!(
+            users["content"]["application/json"]["schema"]["properties"]["manager"]["$ref"],
+            "#/components/schemas/User"
+        );
+        assert_eq!(
+            document["paths"]["/users"]["get"]["operationId"],
+            "listUsers"
+        );
+
+        let spec = OpenApiParser::parse(&local.content).unwrap();
+        assert_eq!(spec.operations.len(), 1);
+
+        // Missing sibling files are reported
+        std::fs::remove_file(dir.path().join("schemas/user.yaml")).unwrap();
+        assert!(SpecBundler::load(&dir.path().join("openapi.yaml")).is_err());
+    }
+
+    #[test]
+    fn test_recursive_schema_moved_to_components() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("openapi.yaml"),
+            r##"
+openapi: "3.0.0"
+info:
+  title: Local API
+  version: "1.0.0"
+paths:
+  /tree:
+    get:
+      operationId: getTree
+      responses:
+        '200':
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: "tree.yaml#/Node"
+"##,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("tree.yaml"),
+            r##"
+Node:
+  type: object
+  properties:
+    children:
+      type: array
+      items:
+        $ref: "#/Node"
+"##,
+        )
+        .unwrap();
+
+        let local = SpecBundler::load(&dir.path().join("openapi.yaml")).unwrap();
+        let document: Value = serde_json::from_str(&local.content).unwrap();
+        let node = &document["components"]["schemas"]["Node"];
+        assert_eq!(node["type"], "object");
+        assert_eq!(
+            node["properties"]["children"]["items"]["$ref"],
+            "#/components/schemas/Node"
+        );
+
+        let spec = OpenApiParser::parse(&local.content).unwrap();
+        assert_eq!(spec.operations.len(), 1);
+    }
+}
@@ -34,4 +34,7 @@ pub enum ParseError {
 
     #[error("Unsupported OpenAPI version: {0}")]
     UnsupportedVersion(String),
+
+    #[error("Invalid overlay: {0}")]
+    InvalidOverlay(String),
 }
@@ -4,9 +4,11 @@
 //! Extracts operations from OpenAPI specs and builds namespace trees for fast lookup.
 
 mod auth;
+mod bundler;
 mod error;
 mod namespace;
 mod operations;
+mod overlay;
 mod parser;
 mod resolver;
 mod types;
@@ -15,5 +17,6 @@ pub use auth::AuthScheme;
 pub use error::{ParseError, ParseResult};
 pub use namespace::NamespaceTree;
 pub use operations::OperationExtractor;
+pub use overlay::{Overlay, OverlayAction, OverlayInfo};
 pub use parser::OpenApiParser;
 pub use types::*;
@@ -185,6 +185,7 @@ mod tests {
                 request_body: None,
                 responses: vec![],
                 security: vec![],
+                public: false,
             },
             ApiOperation {
                 operation_id: "getCustomer".to_string(),
@@ -199,6 +200,7 @@ mod tests {
                 request_body: None,
                 responses: vec![],
                 security: vec![],
+                public: false,
             },
             ApiOperation {
                 operation_id: "listCustomers".to_string(),
@@ -213,6 +215,7 @@ mod tests {
                 request_body: None,
                 responses: vec![],
                 security: vec![],
+                public: false,
             },
         ];
 
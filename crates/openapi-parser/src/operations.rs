@@ -106,7 +106,9 @@ impl OperationExtractor {
         let responses = Self::extract_responses(&operation.responses);
 
         // Extract security requirements
-        let security = Self::extract_security(operation.security.as_ref(), &spec.security);
+        let effective_security = operation.security.as_ref().or(spec.security.as_ref());
+        let security = Self::extract_security(effective_security);
+        let public = effective_security.is_some_and(|s| s.is_empty());
 
         Ok(ApiOperation {
             operation_id,
@@ -121,6 +123,7 @@ impl OperationExtractor {
             request_body,
             responses,
             security,
+            public,
         })
     }
 
@@ -256,21 +259,19 @@ impl OperationExtractor {
             .collect()
     }
 
-    /// Extract security requirements
+    /// Extract security requirements (operation-level, falling back to global)
     fn extract_security(
-        operation_security: Option<&Vec<IndexMap<String, Vec<String>>>>,
-        global_security: &[IndexMap<String, Vec<String>>],
+        security: Option<&Vec<IndexMap<String, Vec<String>>>>,
     ) -> Vec<SecurityRequirement> {
-        let security: &[IndexMap<String, Vec<String>>] = operation_security
-            .map(|v| v.as_slice())
-            .unwrap_or(global_security);
-
         security
-            .iter()
-            .flat_map(|req| {
-                req.iter().map(|(name, scopes)| SecurityRequirement {
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .flat_map(|(alternative, req)| {
+                req.iter().map(move |(name, scopes)| SecurityRequirement {
                     scheme_name: name.clone(),
                     scopes: scopes.clone(),
+                    alternative,
                 })
             })
             .collect()
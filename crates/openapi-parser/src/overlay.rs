@@ -0,0 +1,229 @@
+//! OpenAPI Overlay support
+//!
+//! An overlay (https://spec.openapis.org/overlay/v1.0.0) is a list of
+//! actions applied to a spec document before it is parsed. Each action
+//! selects nodes with a JSONPath expression and either merges an update into
+//! them or removes them, which lets users fix a spec they don't control.
+
+use crate::error::{ParseError, ParseResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use tracing::debug;
+
+/// An OpenAPI Overlay document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Overlay {
+    /// Overlay specification version (e.g., "1.0.0")
+    pub overlay: String,
+
+    /// Overlay metadata
+    pub info: OverlayInfo,
+
+    /// URL of the spec the overlay was written for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Actions, applied in order
+    pub actions: Vec<OverlayAction>,
+}
+
+/// Overlay metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverlayInfo {
+    /// Title
+    pub title: String,
+
+    /// Overlay version
+    pub version: String,
+}
+
+/// A change to the nodes selected by a JSONPath expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverlayAction {
+    /// JSONPath expression selecting the nodes to change
+    pub target: String,
+
+    /// Description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Value merged into objects or appended to arrays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<Value>,
+
+    /// Remove the selected nodes
+    #[serde(default)]
+    pub remove: bool,
+}
+
+impl Overlay {
+    /// Parse an overlay document (auto-detects JSON/YAML)
+    pub fn parse(content: &str) -> ParseResult<Self> {
+        let overlay: Self = if content.trim().starts_with('{') {
+            serde_json::from_str(content)?
+        } else {
+            serde_yaml::from_str(content)?
+        };
+
+        if !overlay.overlay.starts_with("1.") {
+            return Err(ParseError::InvalidOverlay(format!(
+                "unsupported overlay version {}",
+                overlay.overlay
+            )));
+        }
+        for action in &overlay.actions {
+            JsonPath::parse(&action.target).map_err(|e| {
+                ParseError::InvalidOverlay(format!("invalid target {}: {}", action.target, e))
+            })?;
+        }
+
+        Ok(overlay)
+    }
+
+    /// Apply the actions to a spec document
+    ///
+    /// Updates are merged recursively into objects (nested objects are
+    /// merged, other values replaced) and appended to arrays. Targets that
+    /// select nothing are skipped.
+    pub fn apply(&self, document: &mut Value) -> ParseResult<()> {
+        for action in &self.actions {
+            let path = JsonPath::parse(&action.target).map_err(|e| {
+                ParseError::InvalidOverlay(format!("invalid target {}: {}", action.target, e))
+            })?;
+            let pointers: Vec<String> = path
+                .query_located(document)
+                .locations()
+                .map(|location| location.to_json_pointer())
+                .collect();
+
+            if pointers.is_empty() {
+                debug!("Overlay target {} selects nothing", action.target);
+                continue;
+            }
+
+            if action.remove {
+                // Last first, so removing array items keeps earlier indices valid
+                for pointer in pointers.iter().rev() {
+                    remove_pointer(document, pointer);
+                }
+            } else if let Some(update) = &action.update {
+                for pointer in &pointers {
+                    if let Some(node) = document.pointer_mut(pointer) {
+                        match node {
+                            Value::Array(items) => items.push(update.clone()),
+                            node => merge(node, update),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Merge `update` into `target`
+fn merge(target: &mut Value, update: &Value) {
+    match (target, update) {
+        (Value::Object(target), Value::Object(update)) => {
+            for (key, value) in update {
+                match target.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, update) => *target = update.clone(),
+    }
+}
+
+/// Remove the node at a JSON pointer from its parent
+fn remove_pointer(document: &mut Value, pointer: &str) {
+    let Some((parent, last)) = pointer.rsplit_once('/') else {
+        // The root itself cannot be removed
+        return;
+    };
+    let key = last.replace("~1", "/").replace("~0", "~");
+    match document.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.remove(&key);
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(index) = key.parse::<usize>() {
+                if index < items.len() {
+                    items.remove(index);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const OVERLAY: &str = r#"
+overlay: 1.0.0
+info:
+  title: Fixes
+  version: "1"
+actions:
+  - target: $.paths['/users'].get
+    update:
+      summary: List users
+      tags: [users]
+  - target: $.paths.*[?@.deprecated == true]
+    remove: true
+  - target: $.paths['/users'].get.parameters
+    update:
+      name: limit
+      in: query
+"#;
+
+    #[test]
+    fn test_apply_overlay() {
+        let overlay = Overlay::parse(OVERLAY).unwrap();
+        let mut document = json!({
+            "paths": {
+                "/users": {
+                    "get": {"operationId": "listUsers", "summary": "Lists", "parameters": []},
+                    "delete": {"operationId": "purgeUsers", "deprecated": true}
+                },
+                "/legacy": {
+                    "get": {"operationId": "legacy", "deprecated": true}
+                }
+            }
+        });
+
+        overlay.apply(&mut document).unwrap();
+
+        let get = &document["paths"]["/users"]["get"];
+        assert_eq!(get["summary"], "List users");
+        assert_eq!(get["operationId"], "listUsers");
+        assert_eq!(get["tags"], json!(["users"]));
+        assert_eq!(get["parameters"], json!([{"name": "limit", "in": "query"}]));
+        assert!(document["paths"]["/users"].get("delete").is_none());
+        assert_eq!(document["paths"]["/legacy"], json!({}));
+    }
+
+    #[test]
+    fn test_invalid_overlay() {
+        let bad_target = r#"{"overlay": "1.0.0", "info": {"title": "t", "version": "1"},
+            "actions": [{"target": "$.paths[", "remove": true}]}"#;
+        assert!(matches!(
+            Overlay::parse(bad_target),
+            Err(ParseError::InvalidOverlay(_))
+        ));
+
+        let bad_version =
+            r#"{"overlay": "2.0.0", "info": {"title": "t", "version": "1"}, "actions": []}"#;
+        assert!(matches!(
+            Overlay::parse(bad_version),
+            Err(ParseError::InvalidOverlay(_))
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 //! Main OpenAPI parser
 
+use crate::bundler::SpecBundler;
 use crate::error::{ParseError, ParseResult};
 use crate::operations::OperationExtractor;
+use crate::overlay::Overlay;
 use crate::types::*;
 use regex::Regex;
 use std::collections::HashMap;
@@ -40,10 +42,24 @@ impl OpenApiParser {
         Self::convert_spec(raw_spec)
     }
 
+    /// Parse an OpenAPI spec with an overlay applied before extracting operations
+    pub fn parse_with_overlay(content: &str, overlay: &Overlay) -> ParseResult<ParsedSpec> {
+        let content = Self::sanitize_large_numbers(content);
+
+        let mut document: serde_json::Value = if content.trim().starts_with('{') {
+            serde_json::from_str(&content)?
+        } else {
+            serde_yaml::from_str(&content)?
+        };
+        overlay.apply(&mut document)?;
+
+        Self::convert_spec(serde_json::from_value(document)?)
+    }
+
     /// Sanitize large numbers that may cause parsing issues
     /// Some OpenAPI specs (like OpenAI) use very large numbers for min/max values
     /// which can cause serde_yaml to fail with "JSON number out of range"
-    fn sanitize_large_numbers(content: &str) -> String {
+    pub(crate) fn sanitize_large_numbers(content: &str) -> String {
         // Replace any integer that's too large for safe JSON parsing (> 15 digits)
         // These are typically used for min/max constraints and the exact value doesn't matter
         let re_large = Regex::new(
@@ -63,8 +79,41 @@ impl OpenApiParser {
         content.into_owned()
     }
 
+    /// Read an OpenAPI spec from a local file
+    ///
+    /// Relative `$ref`s to other files are resolved against the referencing
+    /// file's directory and inlined.
+    pub fn load_file(path: &std::path::Path) -> ParseResult<LocalSpec> {
+        info!("Loading OpenAPI spec from: {}", path.display());
+        SpecBundler::load(path)
+    }
+
     /// Fetch and parse an OpenAPI spec from a URL
     pub async fn fetch_and_parse(url: &str) -> ParseResult<ParsedSpec> {
+        let content = Self::fetch(url).await?;
+        Self::parse_fetched(url, &content)
+    }
+
+    /// Fetch the raw content of an OpenAPI spec from a URL
+    pub async fn fetch(url: &str) -> ParseResult<String> {
+        match Self::fetch_if_modified(url, None, None).await? {
+            Some(fetched) => Ok(fetched.content),
+            None => Err(ParseError::FetchError(format!(
+                "HTTP 304 from {} without a conditional request",
+                url
+            ))),
+        }
+    }
+
+    /// Fetch a spec unless it is unchanged since the given validators
+    ///
+    /// Sends `If-None-Match`/`If-Modified-Since` when `etag`/`last_modified`
+    /// are set and returns `None` when the server answers 304 Not Modified.
+    pub async fn fetch_if_modified(
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> ParseResult<Option<FetchedSpec>> {
         info!("Fetching OpenAPI spec from: {}", url);
 
         let client = reqwest::Client::builder()
@@ -72,13 +121,26 @@ impl OpenApiParser {
             .build()
             .map_err(|e| ParseError::HttpError(e.to_string()))?;
 
-        let response = client
+        let mut request = client
             .get(url)
-            .header("Accept", "application/json, application/yaml, text/yaml")
+            .header("Accept", "application/json, application/yaml, text/yaml");
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+
+        let response = request
             .send()
             .await
             .map_err(|e| ParseError::FetchError(e.to_string()))?;
 
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            debug!("Spec at {} not modified", url);
+            return Ok(None);
+        }
+
         if !response.status().is_success() {
             return Err(ParseError::FetchError(format!(
                 "HTTP {} from {}",
@@ -87,23 +149,34 @@ impl OpenApiParser {
             )));
         }
 
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string())
-            .unwrap_or_default();
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        let etag = header("etag");
+        let last_modified = header("last-modified");
 
         let content = response
             .text()
             .await
             .map_err(|e| ParseError::FetchError(e.to_string()))?;
 
-        // Parse based on content type or file extension
-        if content_type.contains("yaml") || url.ends_with(".yaml") || url.ends_with(".yml") {
-            Self::parse_yaml(&content)
+        Ok(Some(FetchedSpec {
+            content,
+            etag,
+            last_modified,
+        }))
+    }
+
+    /// Parse spec content fetched from a URL (YAML if the URL says so)
+    pub fn parse_fetched(url: &str, content: &str) -> ParseResult<ParsedSpec> {
+        if url.ends_with(".yaml") || url.ends_with(".yml") {
+            Self::parse_yaml(content)
         } else {
-            Self::parse(&content)
+            Self::parse(content)
         }
     }
 
@@ -132,10 +205,13 @@ impl OpenApiParser {
         let global_security = raw
             .security
             .iter()
-            .flat_map(|req| {
-                req.iter().map(|(name, scopes)| SecurityRequirement {
+            .flatten()
+            .enumerate()
+            .flat_map(|(alternative, req)| {
+                req.iter().map(move |(name, scopes)| SecurityRequirement {
                     scheme_name: name.clone(),
                     scopes: scopes.clone(),
+                    alternative,
                 })
             })
             .collect();
@@ -147,6 +223,18 @@ impl OpenApiParser {
             .map(|s| ServerInfo {
                 url: s.url.clone(),
                 description: s.description.clone(),
+                variables: s
+                    .variables
+                    .iter()
+                    .map(|(name, v)| {
+                        let variable = ServerVariable {
+                            default: v.default.clone(),
+                            enum_values: v.enum_values.clone(),
+                            description: v.description.clone(),
+                        };
+                        (name.clone(), variable)
+                    })
+                    .collect(),
             })
             .collect();
 
@@ -232,6 +320,7 @@ impl OpenApiParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
 
     const SAMPLE_SPEC: &str = r#"
 openapi: "3.0.0"
@@ -334,6 +423,93 @@ security:
         assert_eq!(spec.global_security[0].scheme_name, "bearerAuth");
     }
 
+    #[test]
+    fn test_parse_public_operation() {
+        let yaml = r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+paths:
+  /health:
+    get:
+      operationId: health
+      security: []
+      responses:
+        '200':
+          description: OK
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        '200':
+          description: OK
+components:
+  securitySchemes:
+    bearerAuth:
+      type: http
+      scheme: bearer
+security:
+  - bearerAuth: []
+"#;
+        let spec = OpenApiParser::parse_yaml(yaml).unwrap();
+
+        let health = spec
+            .operations
+            .iter()
+            .find(|op| op.operation_id == "health")
+            .unwrap();
+        assert!(health.public);
+        assert!(health.security.is_empty());
+
+        let list_users = spec
+            .operations
+            .iter()
+            .find(|op| op.operation_id == "listUsers")
+            .unwrap();
+        assert!(!list_users.public);
+        assert_eq!(list_users.security[0].scheme_name, "bearerAuth");
+
+        // No security declared anywhere is not the same as explicitly public
+        let spec = OpenApiParser::parse_yaml(
+            SAMPLE_SPEC
+                .replace("security:\n  - bearerAuth: []\n", "")
+                .as_str(),
+        )
+        .unwrap();
+        assert!(spec.global_security.is_empty());
+        assert!(spec.operations.iter().all(|op| !op.public));
+    }
+
+    #[test]
+    fn test_parse_security_alternatives() {
+        let yaml = r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      security:
+        - apiKey: []
+          appId: []
+        - oauth: [read]
+      responses:
+        '200':
+          description: OK
+"#;
+        let spec = OpenApiParser::parse_yaml(yaml).unwrap();
+
+        let security: Vec<(&str, usize)> = spec.operations[0]
+            .security
+            .iter()
+            .map(|req| (req.scheme_name.as_str(), req.alternative))
+            .collect();
+        assert_eq!(security, [("apiKey", 0), ("appId", 0), ("oauth", 1)]);
+    }
+
     #[test]
     fn test_sanitize_large_numbers() {
         let yaml_with_large_nums = r#"
@@ -360,6 +536,87 @@ components:
         assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
     }
 
+    #[test]
+    fn test_parse_server_variables() {
+        let spec = OpenApiParser::parse(
+            r#"
+openapi: "3.0.0"
+info:
+  title: Regional API
+  version: "1.0.0"
+servers:
+  - url: https://{region}.api.example.com/{version}
+    description: Production
+    variables:
+      region:
+        default: us
+        enum: [us, eu]
+      version:
+        default: v2
+  - url: https://sandbox.example.com
+paths: {}
+"#,
+        )
+        .unwrap();
+
+        let server = &spec.servers[0];
+        assert_eq!(server.variables["region"].enum_values, vec!["us", "eu"]);
+        assert_eq!(
+            server.url_with(&BTreeMap::new()).unwrap(),
+            "https://us.api.example.com/v2"
+        );
+
+        let values = BTreeMap::from([("region".to_string(), "eu".to_string())]);
+        assert_eq!(
+            server.url_with(&values).unwrap(),
+            "https://eu.api.example.com/v2"
+        );
+
+        let values = BTreeMap::from([("region".to_string(), "ap".to_string())]);
+        assert!(server.url_with(&values).is_err());
+        let values = BTreeMap::from([("zone".to_string(), "a".to_string())]);
+        assert!(server.url_with(&values).is_err());
+
+        assert!(spec.servers[1].variables.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_overlay() {
+        let overlay = Overlay::parse(
+            r#"
+overlay: 1.0.0
+info:
+  title: Fixes
+  version: "1"
+actions:
+  - target: $.paths['/users'].post
+    remove: true
+  - target: $.paths['/users'].get
+    update:
+      operationId: listAllUsers
+  - target: $.servers[0]
+    update:
+      url: https://eu.example.com/v1
+"#,
+        )
+        .unwrap();
+
+        let spec = OpenApiParser::parse_with_overlay(SAMPLE_SPEC, &overlay).unwrap();
+        assert!(spec
+            .operations
+            .iter()
+            .any(|op| op.operation_id == "listAllUsers"));
+        assert!(!spec
+            .operations
+            .iter()
+            .any(|op| op.operation_id == "listUsers"));
+        assert!(!spec
+            .operations
+            .iter()
+            .any(|op| op.operation_id == "createUser"));
+        assert_eq!(spec.servers[0].url, "https://eu.example.com/v1");
+    }
+
     #[tokio::test]
     async fn test_fetch_openai_spec() {
         let url = "https://app.stainless.com/api/spec/documented/openai/openapi.documented.yml";
@@ -2,7 +2,9 @@
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::{ParseError, ParseResult};
 
 /// HTTP methods supported by OpenAPI
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -121,15 +123,24 @@ pub struct ApiOperation {
     pub responses: Vec<ResponseSchema>,
     /// Security requirements for this operation
     pub security: Vec<SecurityRequirement>,
+    /// Whether the spec explicitly requires no authentication (`security: []`)
+    #[serde(default)]
+    pub public: bool,
 }
 
 /// Security requirement for an operation
+///
+/// Requirements with the same `alternative` must all be met; meeting any
+/// one alternative is enough.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityRequirement {
     /// Name of the security scheme
     pub scheme_name: String,
     /// Required scopes (for OAuth2)
     pub scopes: Vec<String>,
+    /// Index of the alternative this requirement belongs to
+    #[serde(default)]
+    pub alternative: usize,
 }
 
 /// Parsed OpenAPI specification
@@ -151,6 +162,28 @@ pub struct ParsedSpec {
     pub global_security: Vec<SecurityRequirement>,
 }
 
+/// Spec content downloaded from a URL, with its cache validators
+#[derive(Debug, Clone)]
+pub struct FetchedSpec {
+    /// Raw spec content
+    pub content: String,
+    /// `ETag` response header
+    pub etag: Option<String>,
+    /// `Last-Modified` response header
+    pub last_modified: Option<String>,
+}
+
+/// Spec content read from a local file, with its file references inlined
+#[derive(Debug, Clone)]
+pub struct LocalSpec {
+    /// Spec content (bundled into one JSON document if it references other files)
+    pub content: String,
+    /// Absolute path of the spec file
+    pub path: std::path::PathBuf,
+    /// The spec file and the files it references
+    pub files: Vec<std::path::PathBuf>,
+}
+
 /// Server information from the spec
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInfo {
@@ -158,6 +191,51 @@ pub struct ServerInfo {
     pub url: String,
     /// Server description
     pub description: Option<String>,
+    /// Variables substituted into `{name}` placeholders of the URL
+    #[serde(default)]
+    pub variables: BTreeMap<String, ServerVariable>,
+}
+
+impl ServerInfo {
+    /// Server URL with variables substituted
+    ///
+    /// Variables without a value use their default. Values must be one of
+    /// the variable's allowed values when it lists any.
+    pub fn url_with(&self, values: &BTreeMap<String, String>) -> ParseResult<String> {
+        if let Some(unknown) = values.keys().find(|k| !self.variables.contains_key(*k)) {
+            return Err(ParseError::InvalidUrl(format!(
+                "{} has no variable {}",
+                self.url, unknown
+            )));
+        }
+
+        let mut url = self.url.clone();
+        for (name, variable) in &self.variables {
+            let value = values.get(name).unwrap_or(&variable.default);
+            if !variable.enum_values.is_empty() && !variable.enum_values.contains(value) {
+                return Err(ParseError::InvalidUrl(format!(
+                    "{} is not an allowed value of {} (expected one of {})",
+                    value,
+                    name,
+                    variable.enum_values.join(", ")
+                )));
+            }
+            url = url.replace(&format!("{{{}}}", name), value);
+        }
+        Ok(url)
+    }
+}
+
+/// A server URL variable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerVariable {
+    /// Value used when none is chosen
+    pub default: String,
+    /// Allowed values (any value if empty)
+    #[serde(default, rename = "enum")]
+    pub enum_values: Vec<String>,
+    /// Variable description
+    pub description: Option<String>,
 }
 
 /// Security scheme definition
@@ -222,7 +300,7 @@ pub struct RawOpenApiSpec {
     #[serde(default)]
     pub components: Option<RawComponents>,
     #[serde(default)]
-    pub security: Vec<IndexMap<String, Vec<String>>>,
+    pub security: Option<Vec<IndexMap<String, Vec<String>>>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -236,6 +314,16 @@ pub struct RawInfo {
 pub struct RawServer {
     pub url: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub variables: IndexMap<String, RawServerVariable>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawServerVariable {
+    pub default: String,
+    #[serde(default, rename = "enum")]
+    pub enum_values: Vec<String>,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
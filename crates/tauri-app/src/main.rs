@@ -8,14 +8,24 @@
 )]
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::State;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
-use mcp_server::ServerMode;
-use wallet_core::credential::{Credential, CredentialType};
-use wallet_core::integration::{Integration, IntegrationStatus};
+use mcp_server::{ServerMode, ToolExecutor};
+use openapi_parser::{AuthScheme, ServerInfo};
+use wallet_core::credential::import::{self, ImportCandidate, ImportSource, ScrubAction};
+use wallet_core::credential::{
+    suggest_provider, AuditEntry, Credential, CredentialType, CredentialUsage, ExternalSource,
+    OAuth2Client, PendingAuthorization,
+};
+use wallet_core::integration::{
+    CredentialBinding, Integration, IntegrationStatus, IntegrationTemplate, OperationFilter,
+    SigningConfig, SpecChange, SpecHistoryEntry, SpecSource,
+};
 use wallet_core::settings::OtelSettings;
 use wallet_core::{Wallet, WalletState as CoreWalletState};
 
@@ -23,8 +33,13 @@ use wallet_core::{Wallet, WalletState as CoreWalletState};
 pub struct AppState {
     pub wallet: Arc<RwLock<Wallet>>,
     pub server: Arc<RwLock<Option<ServerHandle>>>,
+    /// OAuth2 logins waiting for the browser redirect, keyed by integration
+    pub oauth2_logins: Arc<Mutex<HashMap<String, PendingAuthorization>>>,
 }
 
+/// How long to wait for the user to finish an OAuth2 login in the browser
+const OAUTH2_LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// Handle to a running MCP server
 pub struct ServerHandle {
     pub mode: ServerMode,
@@ -58,11 +73,33 @@ pub struct IntegrationResponse {
     pub key: String,
     pub name: String,
     pub description: Option<String>,
+    pub provider: String,
     pub spec_url: Option<String>,
+    pub spec_path: Option<String>,
     pub server_url: String,
+    pub server_index: usize,
+    pub server_variables: BTreeMap<String, String>,
+    pub server_error: Option<String>,
     pub status: String,
     pub credential_id: Option<String>,
+    pub credential_bindings: Vec<CredentialBindingResponse>,
+    pub environment: Option<String>,
+    pub auth_scheme: Option<AuthScheme>,
+    pub auth_override: Option<AuthScheme>,
+    pub signing: Option<SigningConfig>,
+    pub probe_operation: Option<String>,
+    pub operation_filter: OperationFilter,
+    pub tool_name_collisions: Vec<String>,
+    pub health_check_interval_secs: Option<u64>,
+    pub next_health_check_at: Option<String>,
+    pub last_validated_at: Option<String>,
     pub operation_count: usize,
+    pub revision: u32,
+    pub spec_version: Option<String>,
+    pub confirm_breaking_sync: bool,
+    pub sync_interval_secs: Option<u64>,
+    pub next_sync_at: Option<String>,
+    pub sync_failures: u32,
     pub last_synced_at: Option<String>,
     pub error: Option<String>,
     pub created_at: String,
@@ -83,11 +120,42 @@ impl From<Integration> for IntegrationResponse {
             key: i.key.clone(),
             name: i.name.clone(),
             description: i.description.clone(),
+            provider: i.credential_provider().to_string(),
             spec_url: i.spec_url.clone(),
+            spec_path: i.spec_path.clone(),
             server_url: i.server_url.clone(),
+            server_index: i.server_index,
+            server_variables: i.server_variables.clone(),
+            server_error: i.server_error.clone(),
             status: status.to_string(),
             credential_id: i.credential_id.map(|id| id.to_string()),
+            credential_bindings: i
+                .credential_bindings
+                .iter()
+                .map(|b| CredentialBindingResponse {
+                    label: b.label.clone(),
+                    credential_id: b.credential_id.to_string(),
+                    environment: b.environment.clone(),
+                    server_url: b.server_url.clone(),
+                })
+                .collect(),
+            environment: i.environment.clone(),
+            auth_scheme: i.auth_scheme.clone(),
+            auth_override: i.auth_override.clone(),
+            signing: i.signing.clone(),
+            probe_operation: i.probe_operation.clone(),
+            operation_filter: i.operation_filter.clone(),
+            tool_name_collisions: i.tool_name_collisions.clone(),
+            health_check_interval_secs: i.health_check_interval_secs,
+            next_health_check_at: i.next_health_check_at.map(|dt| dt.to_rfc3339()),
+            last_validated_at: i.last_validated_at.map(|dt| dt.to_rfc3339()),
             operation_count: i.operation_count,
+            revision: i.revision,
+            spec_version: i.spec_version.clone(),
+            confirm_breaking_sync: i.confirm_breaking_sync,
+            sync_interval_secs: i.sync_interval_secs,
+            next_sync_at: i.next_sync_at.map(|dt| dt.to_rfc3339()),
+            sync_failures: i.sync_failures,
             last_synced_at: i.last_synced_at.map(|dt| dt.to_rfc3339()),
             error: i.error.clone(),
             created_at: i.created_at.to_rfc3339(),
@@ -96,6 +164,97 @@ impl From<Integration> for IntegrationResponse {
     }
 }
 
+/// One spec change found by a sync, for frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecChangeResponse {
+    pub change: SpecChange,
+    pub description: String,
+    pub breaking: bool,
+}
+
+impl From<SpecChange> for SpecChangeResponse {
+    fn from(change: SpecChange) -> Self {
+        Self {
+            description: change.to_string(),
+            breaking: change.is_breaking(),
+            change,
+        }
+    }
+}
+
+/// Sync outcome for frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReportResponse {
+    pub changes: Vec<SpecChangeResponse>,
+    pub applied: bool,
+    pub revision: u32,
+    pub integration: IntegrationResponse,
+}
+
+/// Spec history entry for frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecHistoryEntryResponse {
+    pub revision: u32,
+    pub source: SpecSource,
+    pub spec_version: Option<String>,
+    pub spec_hash: Option<String>,
+    pub operation_count: usize,
+    pub synced_at: Option<String>,
+    pub replaced_at: Option<String>,
+}
+
+impl From<SpecHistoryEntry> for SpecHistoryEntryResponse {
+    fn from(e: SpecHistoryEntry) -> Self {
+        Self {
+            revision: e.revision,
+            source: e.source,
+            spec_version: e.spec_version,
+            spec_hash: e.spec_hash,
+            operation_count: e.operation_count,
+            synced_at: e.synced_at.map(|dt| dt.to_rfc3339()),
+            replaced_at: e.replaced_at.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
+/// Integration template for frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationTemplateResponse {
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub key: String,
+    pub provider: Option<String>,
+    pub builtin: bool,
+}
+
+impl From<&IntegrationTemplate> for IntegrationTemplateResponse {
+    fn from(t: &IntegrationTemplate) -> Self {
+        Self {
+            name: t.name.clone(),
+            title: t.title.clone(),
+            description: t.description.clone(),
+            key: t.key().to_string(),
+            provider: t.provider.clone(),
+            builtin: t.is_builtin(),
+        }
+    }
+}
+
+/// Labeled credential binding for frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialBindingResponse {
+    pub label: String,
+    pub credential_id: String,
+    pub environment: Option<String>,
+    pub server_url: Option<String>,
+}
+
 /// Credential response for frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -105,17 +264,74 @@ pub struct CredentialResponse {
     pub name: String,
     pub credential_type: String,
     pub prefix: Option<String>,
+    pub username: Option<String>,
+    pub field_names: Vec<String>,
+    pub granted_scopes: Option<Vec<String>>,
     pub integration_id: Option<String>,
     pub last_used_at: Option<String>,
+    pub usage: CredentialUsageResponse,
     pub created_at: String,
 }
 
+/// Credential usage counters response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialUsageResponse {
+    pub use_count: u64,
+    pub error_count: u64,
+    pub error_rate: f64,
+    pub last_integration: Option<String>,
+    pub last_operation: Option<String>,
+    pub last_status: Option<u16>,
+}
+
+impl From<CredentialUsage> for CredentialUsageResponse {
+    fn from(u: CredentialUsage) -> Self {
+        Self {
+            error_rate: u.error_rate(),
+            use_count: u.use_count,
+            error_count: u.error_count,
+            last_integration: u.last_integration,
+            last_operation: u.last_operation,
+            last_status: u.last_status,
+        }
+    }
+}
+
+/// Credential audit log entry response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntryResponse {
+    pub timestamp: String,
+    pub credential_id: String,
+    pub action: String,
+    pub requester: Option<String>,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(e: AuditEntry) -> Self {
+        Self {
+            timestamp: e.timestamp.to_rfc3339(),
+            credential_id: e.credential_id.to_string(),
+            action: serde_json::to_value(e.action)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default(),
+            requester: e.requester,
+        }
+    }
+}
+
 impl From<Credential> for CredentialResponse {
     fn from(c: Credential) -> Self {
         let cred_type = match c.credential_type {
             CredentialType::ApiKey => "api_key",
             CredentialType::OAuth2Token => "oauth2_token",
+            CredentialType::OAuth2ClientCredentials => "oauth2_client_credentials",
             CredentialType::BasicAuth => "basic_auth",
+            CredentialType::MultiField => "multi_field",
+            CredentialType::ClientCertificate => "client_certificate",
+            CredentialType::External => "external",
         };
 
         Self {
@@ -124,13 +340,53 @@ impl From<Credential> for CredentialResponse {
             name: c.name.clone(),
             credential_type: cred_type.to_string(),
             prefix: c.prefix.clone(),
+            username: c.username.clone(),
+            field_names: c.field_names.clone(),
+            granted_scopes: c.granted_scopes.clone(),
             integration_id: c.integration_id.map(|id| id.to_string()),
             last_used_at: c.last_used_at.map(|dt| dt.to_rfc3339()),
+            usage: CredentialUsageResponse::from(c.usage.clone()),
             created_at: c.created_at.to_rfc3339(),
         }
     }
 }
 
+/// Secret found by the importer (the value itself is never sent to the frontend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCandidateResponse {
+    pub variable: String,
+    pub masked: String,
+    pub source: String,
+    pub provider: Option<String>,
+    pub integration: Option<String>,
+}
+
+impl From<&ImportCandidate> for ImportCandidateResponse {
+    fn from(c: &ImportCandidate) -> Self {
+        // Only long values show a few trailing characters to tell them apart
+        let value = c.value.expose();
+        let count = value.chars().count();
+        let masked = if count >= 16 {
+            let tail: String = value.chars().skip(count - 4).collect();
+            format!("••••{}", tail)
+        } else {
+            "••••••••".to_string()
+        };
+
+        Self {
+            variable: c.variable.clone(),
+            masked,
+            source: match &c.source {
+                ImportSource::DotEnv { path, line } => format!("{}:{}", path.display(), line),
+                ImportSource::Environment => "environment".to_string(),
+            },
+            provider: c.provider.clone(),
+            integration: c.integration.clone(),
+        }
+    }
+}
+
 /// Server status response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -298,6 +554,76 @@ async fn add_integration(
     Ok(IntegrationResponse::from(integration))
 }
 
+#[tauri::command]
+async fn add_integration_from_path(
+    key: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<IntegrationResponse, String> {
+    let wallet = state.wallet.read().await;
+
+    if path.is_empty() {
+        return Err("Spec file path is required to add an integration".to_string());
+    }
+
+    let integration = wallet
+        .integrations
+        .add_from_path(&key, std::path::Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(IntegrationResponse::from(integration))
+}
+
+#[tauri::command]
+async fn list_integration_templates(
+    state: State<'_, AppState>,
+) -> Result<Vec<IntegrationTemplateResponse>, String> {
+    let wallet = state.wallet.read().await;
+    let catalog = wallet.integrations.templates().map_err(|e| e.to_string())?;
+    Ok(catalog
+        .templates
+        .iter()
+        .map(IntegrationTemplateResponse::from)
+        .collect())
+}
+
+#[tauri::command]
+async fn add_integration_from_template(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<IntegrationResponse, String> {
+    let wallet = state.wallet.read().await;
+
+    let mut integration = wallet
+        .integrations
+        .add_from_template(&name)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Use the only credential of the template's provider, if there is exactly one
+    let provider = integration.credential_provider();
+    let credentials = wallet
+        .credentials
+        .list_by_provider(provider)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let [credential] = credentials.as_slice() {
+        wallet
+            .integrations
+            .set_credential(&integration.key, credential.id)
+            .await
+            .map_err(|e| e.to_string())?;
+        integration = wallet
+            .integrations
+            .get(&integration.key)
+            .await
+            .ok_or_else(|| format!("Integration not found: {}", integration.key))?;
+    }
+
+    Ok(IntegrationResponse::from(integration))
+}
+
 #[tauri::command]
 async fn remove_integration(key: String, state: State<'_, AppState>) -> Result<(), String> {
     let wallet = state.wallet.read().await;
@@ -311,12 +637,13 @@ async fn remove_integration(key: String, state: State<'_, AppState>) -> Result<(
 #[tauri::command]
 async fn sync_integration(
     key: String,
+    confirm: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<IntegrationResponse, String> {
+) -> Result<SyncReportResponse, String> {
     let wallet = state.wallet.read().await;
-    wallet
+    let report = wallet
         .integrations
-        .sync(&key)
+        .sync(&key, confirm.unwrap_or(false))
         .await
         .map_err(|e| e.to_string())?;
 
@@ -326,9 +653,198 @@ async fn sync_integration(
         .await
         .ok_or_else(|| format!("Integration '{}' not found after sync", key))?;
 
+    Ok(SyncReportResponse {
+        changes: report.diff.changes.into_iter().map(Into::into).collect(),
+        applied: report.applied,
+        revision: report.revision,
+        integration: IntegrationResponse::from(integration),
+    })
+}
+
+#[tauri::command]
+async fn preview_integration_sync(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SpecChangeResponse>, String> {
+    let wallet = state.wallet.read().await;
+    let diff = wallet
+        .integrations
+        .preview_sync(&key)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(diff.changes.into_iter().map(Into::into).collect())
+}
+
+#[tauri::command]
+async fn get_integration_history(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SpecHistoryEntryResponse>, String> {
+    let wallet = state.wallet.read().await;
+    let history = wallet
+        .integrations
+        .history(&key)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(history.into_iter().map(Into::into).collect())
+}
+
+#[tauri::command]
+async fn rollback_integration(
+    key: String,
+    revision: u32,
+    state: State<'_, AppState>,
+) -> Result<SyncReportResponse, String> {
+    let wallet = state.wallet.read().await;
+    let report = wallet
+        .integrations
+        .rollback(&key, revision)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let integration = wallet
+        .integrations
+        .get(&key)
+        .await
+        .ok_or_else(|| format!("Integration '{}' not found after rollback", key))?;
+
+    Ok(SyncReportResponse {
+        changes: report.diff.changes.into_iter().map(Into::into).collect(),
+        applied: report.applied,
+        revision: report.revision,
+        integration: IntegrationResponse::from(integration),
+    })
+}
+
+#[tauri::command]
+async fn set_confirm_breaking_sync(
+    key: String,
+    confirm: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_confirm_breaking_sync(&key, confirm)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_integration_enabled(
+    key: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_enabled(&key, enabled)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_health_check_interval(
+    key: String,
+    interval_secs: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_health_check_interval(&key, interval_secs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_integration_servers(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ServerInfo>, String> {
+    let wallet = state.wallet.read().await;
+    let stored = wallet
+        .integrations
+        .get_stored(&key)
+        .await
+        .ok_or_else(|| format!("Integration not found: {}", key))?;
+    Ok(stored.servers)
+}
+
+#[tauri::command]
+async fn select_integration_server(
+    key: String,
+    index: usize,
+    variables: BTreeMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<IntegrationResponse, String> {
+    let wallet = state.wallet.read().await;
+    let integration = wallet
+        .integrations
+        .select_server(&key, index, variables)
+        .await
+        .map_err(|e| e.to_string())?;
     Ok(IntegrationResponse::from(integration))
 }
 
+#[tauri::command]
+async fn set_operation_filter(
+    key: String,
+    filter: OperationFilter,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_operation_filter(&key, filter)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_integration_overlay(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let wallet = state.wallet.read().await;
+    let stored = wallet
+        .integrations
+        .get_stored(&key)
+        .await
+        .ok_or_else(|| format!("Integration not found: {}", key))?;
+    Ok(stored.overlay)
+}
+
+#[tauri::command]
+async fn set_integration_overlay(
+    key: String,
+    overlay: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SpecChangeResponse>, String> {
+    let wallet = state.wallet.read().await;
+    let diff = wallet
+        .integrations
+        .set_overlay(&key, overlay)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(diff.changes.into_iter().map(Into::into).collect())
+}
+
+#[tauri::command]
+async fn set_sync_interval(
+    key: String,
+    interval_secs: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_sync_interval(&key, interval_secs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn get_operations(
     integration_key: String,
@@ -395,6 +911,91 @@ async fn get_operations(
 // Credential Commands
 // ============================================================================
 
+/// Find secrets in a dotenv file, or else in the named environment variables
+async fn find_import_candidates(
+    wallet: &Wallet,
+    path: Option<&str>,
+    variables: &[String],
+) -> Result<Vec<ImportCandidate>, String> {
+    let mut candidates = match path {
+        Some(path) => import::parse_dotenv(std::path::Path::new(path))
+            .await
+            .map_err(|e| e.to_string())?,
+        None => {
+            let names: Vec<&str> = variables.iter().map(String::as_str).collect();
+            import::from_environment(&names)
+        }
+    };
+
+    let integrations = wallet.integrations.list().await;
+    for candidate in &mut candidates {
+        candidate.match_integration(&integrations);
+    }
+    Ok(candidates)
+}
+
+#[tauri::command]
+async fn scan_credential_imports(
+    path: Option<String>,
+    variables: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ImportCandidateResponse>, String> {
+    let wallet = state.wallet.read().await;
+    let candidates = find_import_candidates(&wallet, path.as_deref(), &variables).await?;
+    Ok(candidates
+        .iter()
+        .map(ImportCandidateResponse::from)
+        .collect())
+}
+
+#[tauri::command]
+async fn import_credentials(
+    path: Option<String>,
+    variables: Vec<String>,
+    bind: bool,
+    scrub: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<CredentialResponse>, String> {
+    let scrub = match scrub.as_deref() {
+        None => None,
+        Some("remove") => Some(ScrubAction::Remove),
+        Some("comment_out") => Some(ScrubAction::CommentOut),
+        Some(other) => return Err(format!("Unknown scrub action: {}", other)),
+    };
+
+    let wallet = state.wallet.read().await;
+    let candidates = find_import_candidates(&wallet, path.as_deref(), &variables).await?;
+
+    let mut imported = Vec::new();
+    for candidate in candidates
+        .iter()
+        .filter(|c| variables.contains(&c.variable))
+    {
+        let credential = wallet
+            .credentials
+            .import(candidate)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let (true, Some(integration)) = (bind, &candidate.integration) {
+            wallet
+                .integrations
+                .set_credential(integration, credential.id)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        imported.push(credential);
+    }
+
+    if let (Some(path), Some(action)) = (&path, scrub) {
+        let names: Vec<String> = imported.iter().map(|c| c.name.clone()).collect();
+        import::scrub_dotenv(std::path::Path::new(path), &names, action)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(imported.into_iter().map(CredentialResponse::from).collect())
+}
+
 #[tauri::command]
 async fn list_credentials(state: State<'_, AppState>) -> Result<Vec<CredentialResponse>, String> {
     let wallet = state.wallet.read().await;
@@ -405,19 +1006,133 @@ async fn list_credentials(state: State<'_, AppState>) -> Result<Vec<CredentialRe
         .collect())
 }
 
+#[tauri::command]
+async fn get_credential_audit_log(
+    credential_id: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AuditEntryResponse>, String> {
+    let credential_id = credential_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    let wallet = state.wallet.read().await;
+    let entries = wallet
+        .credentials
+        .audit_entries(credential_id, limit)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(entries.into_iter().map(AuditEntryResponse::from).collect())
+}
+
 #[tauri::command]
 async fn add_credential(
-    provider: String,
+    provider: Option<String>,
     name: String,
     api_key: String,
+    integration_key: Option<String>,
+    validate: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<CredentialResponse, String> {
+    // Without a provider, recognize it from the key's prefix
+    let provider = match provider.filter(|p| !p.trim().is_empty()) {
+        Some(provider) => provider,
+        None => suggest_provider(&api_key)
+            .map(String::from)
+            .ok_or("No provider given and none recognized from the key")?,
+    };
+
     let wallet = state.wallet.read().await;
     let credential = wallet
         .credentials
         .add_api_key(&provider, &name, &api_key)
         .await
         .map_err(|e| e.to_string())?;
+    drop(wallet);
+
+    if let Some(integration_key) = integration_key {
+        bind_and_validate(&state, &integration_key, credential.id, validate).await?;
+    }
+    Ok(CredentialResponse::from(credential))
+}
+
+#[tauri::command]
+async fn add_basic_auth_credential(
+    provider: String,
+    name: String,
+    username: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<CredentialResponse, String> {
+    let wallet = state.wallet.read().await;
+    let credential = wallet
+        .credentials
+        .add_basic_auth(&provider, &name, &username, &password)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(CredentialResponse::from(credential))
+}
+
+#[tauri::command]
+async fn add_multi_field_credential(
+    provider: String,
+    name: String,
+    fields: HashMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<CredentialResponse, String> {
+    let wallet = state.wallet.read().await;
+    let fields: Vec<(&str, &str)> = fields
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let credential = wallet
+        .credentials
+        .add_multi_field(&provider, &name, &fields)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(CredentialResponse::from(credential))
+}
+
+#[tauri::command]
+async fn add_client_certificate_credential(
+    provider: String,
+    name: String,
+    certificate_pem: String,
+    private_key_pem: String,
+    ca_bundle_pem: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CredentialResponse, String> {
+    let wallet = state.wallet.read().await;
+    let credential = wallet
+        .credentials
+        .add_client_certificate(
+            &provider,
+            &name,
+            &certificate_pem,
+            &private_key_pem,
+            ca_bundle_pem.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(CredentialResponse::from(credential))
+}
+
+#[tauri::command]
+async fn add_external_credential(
+    provider: String,
+    name: String,
+    source: ExternalSource,
+    ttl_secs: u64,
+    vault_token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CredentialResponse, String> {
+    let wallet = state.wallet.read().await;
+    let credential = wallet
+        .credentials
+        .add_external(&provider, &name, source, ttl_secs, vault_token.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
     Ok(CredentialResponse::from(credential))
 }
 
@@ -436,17 +1151,213 @@ async fn delete_credential(id: String, state: State<'_, AppState>) -> Result<(),
 async fn bind_credential(
     integration_key: String,
     credential_id: String,
+    validate: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let wallet = state.wallet.read().await;
     let cred_uuid = Uuid::parse_str(&credential_id).map_err(|e| e.to_string())?;
+    bind_and_validate(&state, &integration_key, cred_uuid, validate).await
+}
+
+/// Bind a credential to an integration and, if asked, probe that it works
+async fn bind_and_validate(
+    state: &AppState,
+    integration_key: &str,
+    credential_id: Uuid,
+    validate: Option<bool>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
     wallet
         .integrations
-        .set_credential(&integration_key, cred_uuid)
+        .set_credential(integration_key, credential_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(wallet);
+
+    if validate.unwrap_or(false) {
+        ToolExecutor::new(state.wallet.clone())
+            .validate_credential(integration_key, None)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn validate_integration_credential(
+    integration_key: String,
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IntegrationResponse, String> {
+    let integration = ToolExecutor::new(state.wallet.clone())
+        .validate_credential(&integration_key, label.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(IntegrationResponse::from(integration))
+}
+
+#[tauri::command]
+async fn set_probe_operation(
+    integration_key: String,
+    operation: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_probe_operation(&integration_key, operation.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn suggest_credential_provider(api_key: String) -> Result<Option<String>, String> {
+    Ok(suggest_provider(&api_key).map(String::from))
+}
+
+#[tauri::command]
+async fn bind_labeled_credential(
+    integration_key: String,
+    binding: CredentialBindingResponse,
+    make_default: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    let credential_id = Uuid::parse_str(&binding.credential_id).map_err(|e| e.to_string())?;
+    let binding = CredentialBinding {
+        label: binding.label,
+        credential_id,
+        environment: binding.environment,
+        server_url: binding.server_url,
+    };
+    wallet
+        .integrations
+        .bind_credential(&integration_key, binding, make_default)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn unbind_labeled_credential(
+    integration_key: String,
+    label: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .unbind_credential(&integration_key, &label)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_integration_environment(
+    integration_key: String,
+    environment: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_environment(&integration_key, environment.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_auth_override(
+    integration_key: String,
+    scheme: Option<AuthScheme>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_auth_override(&integration_key, scheme)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_signing(
+    integration_key: String,
+    signing: Option<SigningConfig>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_signing(&integration_key, signing)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_credential_field(
+    integration_key: String,
+    field: String,
+    scheme_name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let wallet = state.wallet.read().await;
+    wallet
+        .integrations
+        .set_credential_field(&integration_key, &field, scheme_name.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn start_oauth2_login(
+    integration_key: String,
+    client_id: String,
+    client_secret: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let wallet = state.wallet.read().await;
+    let pending = wallet
+        .begin_oauth2_login(&integration_key, &client_id, client_secret.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(wallet);
+
+    let url = pending.authorization_url().to_string();
+    state
+        .oauth2_logins
+        .lock()
+        .await
+        .insert(integration_key, pending);
+
+    Ok(url)
+}
+
+#[tauri::command]
+async fn finish_oauth2_login(
+    integration_key: String,
+    state: State<'_, AppState>,
+) -> Result<CredentialResponse, String> {
+    let pending = state
+        .oauth2_logins
+        .lock()
+        .await
+        .remove(&integration_key)
+        .ok_or_else(|| format!("No OAuth2 login in progress for '{}'", integration_key))?;
+
+    // Wait without holding the wallet lock so the user can still lock the wallet
+    let tokens = pending
+        .wait_for_tokens(&OAuth2Client::new(), OAUTH2_LOGIN_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let wallet = state.wallet.read().await;
+    let credential = wallet
+        .save_oauth2_login(&integration_key, pending.flow(), &tokens)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(CredentialResponse::from(credential))
+}
+
 // ============================================================================
 // Server Commands
 // ============================================================================
@@ -590,9 +1501,23 @@ async fn set_auto_lock_timeout(minutes: u32, state: State<'_, AppState>) -> Resu
 
 fn main() {
     let wallet = Wallet::new().expect("Failed to create wallet");
+    let wallet = Arc::new(RwLock::new(wallet));
+    tauri::async_runtime::spawn(Wallet::run_sync_scheduler(
+        wallet.clone(),
+        Duration::from_secs(60),
+    ));
+    tauri::async_runtime::spawn(Wallet::run_file_watcher(
+        wallet.clone(),
+        Duration::from_secs(2),
+    ));
+    let health = ToolExecutor::new(wallet.clone());
+    tauri::async_runtime::spawn(
+        async move { health.run_health_checks(Duration::from_secs(60)).await },
+    );
     let app_state = AppState {
-        wallet: Arc::new(RwLock::new(wallet)),
+        wallet,
         server: Arc::new(RwLock::new(None)),
+        oauth2_logins: Arc::new(Mutex::new(HashMap::new())),
     };
 
     tauri::Builder::default()
@@ -606,13 +1531,46 @@ fn main() {
             reset_wallet,
             list_integrations,
             add_integration,
+            add_integration_from_path,
+            list_integration_templates,
+            add_integration_from_template,
             remove_integration,
             sync_integration,
+            preview_integration_sync,
+            set_confirm_breaking_sync,
+            set_sync_interval,
+            set_operation_filter,
+            get_integration_overlay,
+            set_integration_overlay,
+            set_integration_enabled,
+            set_health_check_interval,
+            list_integration_servers,
+            select_integration_server,
+            get_integration_history,
+            rollback_integration,
             get_operations,
             list_credentials,
+            scan_credential_imports,
+            import_credentials,
+            get_credential_audit_log,
             add_credential,
+            add_basic_auth_credential,
+            add_multi_field_credential,
+            add_client_certificate_credential,
+            add_external_credential,
             delete_credential,
             bind_credential,
+            validate_integration_credential,
+            set_probe_operation,
+            suggest_credential_provider,
+            bind_labeled_credential,
+            unbind_labeled_credential,
+            set_integration_environment,
+            set_auth_override,
+            set_signing,
+            set_credential_field,
+            start_oauth2_login,
+            finish_oauth2_login,
             get_server_status,
             start_server,
             stop_server,
@@ -1,5 +1,23 @@
 import { invoke } from "@tauri-apps/api/core";
-import { WalletState, Integration, Credential, ServerStatus, Operation } from "./types";
+import {
+  WalletState,
+  Integration,
+  Credential,
+  ServerStatus,
+  Operation,
+  AuthScheme,
+  CredentialBinding,
+  SigningConfig,
+  OperationFilter,
+  ServerInfo,
+  IntegrationTemplate,
+  AuditEntry,
+  ImportCandidate,
+  ExternalSource,
+  SyncReport,
+  SpecChangeEntry,
+  SpecHistoryEntry,
+} from "./types";
 
 // Wallet state
 export async function getWalletState(): Promise<WalletState> {
@@ -31,12 +49,82 @@ export async function addIntegration(key: string, specUrl: string): Promise<Inte
   return await invoke<Integration>("add_integration", { key, specUrl });
 }
 
+export async function addIntegrationFromPath(key: string, path: string): Promise<Integration> {
+  return await invoke<Integration>("add_integration_from_path", { key, path });
+}
+
 export async function removeIntegration(key: string): Promise<void> {
   await invoke("remove_integration", { key });
 }
 
-export async function syncIntegration(key: string): Promise<Integration> {
-  return await invoke<Integration>("sync_integration", { key });
+export async function syncIntegration(key: string, confirm?: boolean): Promise<SyncReport> {
+  return await invoke<SyncReport>("sync_integration", { key, confirm });
+}
+
+export async function previewIntegrationSync(key: string): Promise<SpecChangeEntry[]> {
+  return await invoke<SpecChangeEntry[]>("preview_integration_sync", { key });
+}
+
+export async function getIntegrationHistory(key: string): Promise<SpecHistoryEntry[]> {
+  return await invoke<SpecHistoryEntry[]>("get_integration_history", { key });
+}
+
+export async function rollbackIntegration(key: string, revision: number): Promise<SyncReport> {
+  return await invoke<SyncReport>("rollback_integration", { key, revision });
+}
+
+export async function setConfirmBreakingSync(key: string, confirm: boolean): Promise<void> {
+  await invoke("set_confirm_breaking_sync", { key, confirm });
+}
+
+export async function listIntegrationTemplates(): Promise<IntegrationTemplate[]> {
+  return await invoke<IntegrationTemplate[]>("list_integration_templates");
+}
+
+export async function addIntegrationFromTemplate(name: string): Promise<Integration> {
+  return await invoke<Integration>("add_integration_from_template", { name });
+}
+
+export async function listIntegrationServers(key: string): Promise<ServerInfo[]> {
+  return await invoke<ServerInfo[]>("list_integration_servers", { key });
+}
+
+export async function selectIntegrationServer(
+  key: string,
+  index: number,
+  variables: Record<string, string>
+): Promise<Integration> {
+  return await invoke<Integration>("select_integration_server", { key, index, variables });
+}
+
+export async function setIntegrationEnabled(key: string, enabled: boolean): Promise<void> {
+  await invoke("set_integration_enabled", { key, enabled });
+}
+
+export async function setHealthCheckInterval(
+  key: string,
+  intervalSecs: number | null
+): Promise<void> {
+  await invoke("set_health_check_interval", { key, intervalSecs });
+}
+
+export async function setOperationFilter(key: string, filter: OperationFilter): Promise<void> {
+  await invoke("set_operation_filter", { key, filter });
+}
+
+export async function getIntegrationOverlay(key: string): Promise<string | null> {
+  return await invoke<string | null>("get_integration_overlay", { key });
+}
+
+export async function setIntegrationOverlay(
+  key: string,
+  overlay: string | null
+): Promise<SpecChangeEntry[]> {
+  return await invoke<SpecChangeEntry[]>("set_integration_overlay", { key, overlay });
+}
+
+export async function setSyncInterval(key: string, intervalSecs: number | null): Promise<void> {
+  await invoke("set_sync_interval", { key, intervalSecs });
 }
 
 // Credentials
@@ -45,19 +133,179 @@ export async function listCredentials(): Promise<Credential[]> {
 }
 
 export async function addCredential(
+  provider: string | null,
+  name: string,
+  apiKey: string,
+  integrationKey?: string,
+  validate?: boolean
+): Promise<Credential> {
+  return await invoke<Credential>("add_credential", {
+    provider,
+    name,
+    apiKey,
+    integrationKey,
+    validate,
+  });
+}
+
+export async function addBasicAuthCredential(
+  provider: string,
+  name: string,
+  username: string,
+  password: string
+): Promise<Credential> {
+  return await invoke<Credential>("add_basic_auth_credential", {
+    provider,
+    name,
+    username,
+    password,
+  });
+}
+
+export async function addMultiFieldCredential(
   provider: string,
   name: string,
-  apiKey: string
+  fields: Record<string, string>
 ): Promise<Credential> {
-  return await invoke<Credential>("add_credential", { provider, name, apiKey });
+  return await invoke<Credential>("add_multi_field_credential", { provider, name, fields });
+}
+
+export async function addClientCertificateCredential(
+  provider: string,
+  name: string,
+  certificatePem: string,
+  privateKeyPem: string,
+  caBundlePem?: string
+): Promise<Credential> {
+  return await invoke<Credential>("add_client_certificate_credential", {
+    provider,
+    name,
+    certificatePem,
+    privateKeyPem,
+    caBundlePem,
+  });
+}
+
+export async function addExternalCredential(
+  provider: string,
+  name: string,
+  source: ExternalSource,
+  ttlSecs: number,
+  vaultToken?: string
+): Promise<Credential> {
+  return await invoke<Credential>("add_external_credential", {
+    provider,
+    name,
+    source,
+    ttlSecs,
+    vaultToken,
+  });
+}
+
+export async function getCredentialAuditLog(
+  credentialId?: string,
+  limit?: number
+): Promise<AuditEntry[]> {
+  return await invoke<AuditEntry[]>("get_credential_audit_log", { credentialId, limit });
+}
+
+export async function scanCredentialImports(
+  path: string | null,
+  variables: string[] = []
+): Promise<ImportCandidate[]> {
+  return await invoke<ImportCandidate[]>("scan_credential_imports", { path, variables });
+}
+
+export async function importCredentials(
+  path: string | null,
+  variables: string[],
+  bind: boolean,
+  scrub?: "remove" | "comment_out"
+): Promise<Credential[]> {
+  return await invoke<Credential[]>("import_credentials", { path, variables, bind, scrub });
 }
 
 export async function deleteCredential(id: string): Promise<void> {
   await invoke("delete_credential", { id });
 }
 
-export async function bindCredential(integrationKey: string, credentialId: string): Promise<void> {
-  await invoke("bind_credential", { integrationKey, credentialId });
+export async function bindCredential(
+  integrationKey: string,
+  credentialId: string,
+  validate?: boolean
+): Promise<void> {
+  await invoke("bind_credential", { integrationKey, credentialId, validate });
+}
+
+export async function validateIntegrationCredential(
+  integrationKey: string,
+  label?: string
+): Promise<Integration> {
+  return await invoke<Integration>("validate_integration_credential", { integrationKey, label });
+}
+
+export async function setProbeOperation(
+  integrationKey: string,
+  operation: string | null
+): Promise<void> {
+  await invoke("set_probe_operation", { integrationKey, operation });
+}
+
+export async function suggestCredentialProvider(apiKey: string): Promise<string | null> {
+  return await invoke<string | null>("suggest_credential_provider", { apiKey });
+}
+
+export async function bindLabeledCredential(
+  integrationKey: string,
+  binding: CredentialBinding,
+  makeDefault: boolean
+): Promise<void> {
+  await invoke("bind_labeled_credential", { integrationKey, binding, makeDefault });
+}
+
+export async function unbindLabeledCredential(integrationKey: string, label: string): Promise<void> {
+  await invoke("unbind_labeled_credential", { integrationKey, label });
+}
+
+export async function setIntegrationEnvironment(
+  integrationKey: string,
+  environment: string | null
+): Promise<void> {
+  await invoke("set_integration_environment", { integrationKey, environment });
+}
+
+export async function setAuthOverride(
+  integrationKey: string,
+  scheme: AuthScheme | null
+): Promise<void> {
+  await invoke("set_auth_override", { integrationKey, scheme });
+}
+
+export async function setSigning(
+  integrationKey: string,
+  signing: SigningConfig | null
+): Promise<void> {
+  await invoke("set_signing", { integrationKey, signing });
+}
+
+export async function setCredentialField(
+  integrationKey: string,
+  field: string,
+  schemeName: string | null
+): Promise<void> {
+  await invoke("set_credential_field", { integrationKey, field, schemeName });
+}
+
+export async function startOAuth2Login(
+  integrationKey: string,
+  clientId: string,
+  clientSecret?: string
+): Promise<string> {
+  return await invoke<string>("start_oauth2_login", { integrationKey, clientId, clientSecret });
+}
+
+export async function finishOAuth2Login(integrationKey: string): Promise<Credential> {
+  return await invoke<Credential>("finish_oauth2_login", { integrationKey });
 }
 
 // Server
@@ -26,35 +26,207 @@ export interface IntegrationDef {
   tags?: string[];
 }
 
+// Authentication scheme (serialized openapi_parser::AuthScheme)
+export type AuthScheme =
+  | "none"
+  | "basic"
+  | { bearer: { format?: string } }
+  | { api_key: { name: string; location: "header" | "query" | "cookie" } }
+  | { o_auth2: { authorization_url?: string; token_url?: string; scopes: string[] } }
+  | { multiple: AuthScheme[] };
+
+// Request signing configuration
+export type SigningConfig =
+  | { type: "aws_sig_v4"; region: string; service: string }
+  | {
+      type: "hmac_sha256";
+      signature_header: string;
+      timestamp_header: string;
+      key_id_header?: string;
+    };
+
+// Integration template from the catalog
+export interface IntegrationTemplate {
+  name: string;
+  title: string;
+  description?: string;
+  key: string;
+  provider?: string;
+  builtin: boolean;
+}
+
+// Server listed by an integration's spec
+export interface ServerInfo {
+  url: string;
+  description?: string;
+  variables: Record<string, { default: string; enum: string[]; description?: string }>;
+}
+
+// Rule selecting operations of an integration
+export type OperationMatcher =
+  | { type: "tag"; tag: string }
+  | { type: "method"; method: string }
+  | { type: "path"; glob: string }
+  | { type: "operation_id"; id: string };
+
+// Which operations of an integration are exposed as tools
+export interface OperationFilter {
+  include: OperationMatcher[];
+  exclude: OperationMatcher[];
+  read_only: boolean;
+}
+
+// Where an external credential's secret is held
+export type ExternalSource =
+  | { type: "pass"; entry: string }
+  | { type: "command"; program: string; args: string[] }
+  | { type: "vault"; address: string; mount: string; path: string; field: string };
+
+// Labeled credential bound to an integration
+export interface CredentialBinding {
+  label: string;
+  credentialId: string;
+  environment?: string;
+  serverUrl?: string;
+}
+
 // Installed integration
 export interface Integration {
   id: string;
   key: string;
   name: string;
   description?: string;
+  provider: string;
   specUrl?: string;
+  specPath?: string;
   serverUrl: string;
+  serverIndex: number;
+  serverVariables: Record<string, string>;
+  serverError?: string;
   status: "pending" | "active" | "error" | "disabled";
   credentialId?: string;
+  credentialBindings: CredentialBinding[];
+  environment?: string;
+  authScheme?: AuthScheme;
+  authOverride?: AuthScheme;
+  signing?: SigningConfig;
+  probeOperation?: string;
+  operationFilter: OperationFilter;
+  toolNameCollisions: string[];
+  healthCheckIntervalSecs?: number;
+  nextHealthCheckAt?: string;
+  lastValidatedAt?: string;
   operationCount: number;
+  revision: number;
+  specVersion?: string;
+  confirmBreakingSync: boolean;
+  syncIntervalSecs?: number;
+  nextSyncAt?: string;
+  syncFailures: number;
   lastSyncedAt?: string;
   error?: string;
   createdAt: string;
   updatedAt: string;
 }
 
+// Change between two versions of an integration's spec
+export type SpecChange =
+  | { kind: "operation_added"; operation: string }
+  | { kind: "operation_removed"; operation: string }
+  | { kind: "operation_renamed"; from: string; to: string }
+  | { kind: "endpoint_changed"; operation: string; from: string; to: string }
+  | { kind: "parameter_added"; operation: string; parameter: string; required: boolean }
+  | { kind: "parameter_removed"; operation: string; parameter: string }
+  | { kind: "parameter_required"; operation: string; parameter: string }
+  | { kind: "parameter_optional"; operation: string; parameter: string }
+  | { kind: "body_added"; operation: string; required: boolean }
+  | { kind: "body_removed"; operation: string }
+  | { kind: "body_required"; operation: string }
+  | { kind: "body_field_required"; operation: string; field: string }
+  | { kind: "security_changed"; operation: string }
+  | { kind: "auth_changed"; from?: AuthScheme; to?: AuthScheme };
+
+export interface SpecChangeEntry {
+  change: SpecChange;
+  description: string;
+  breaking: boolean;
+}
+
+// Outcome of syncing an integration
+export interface SyncReport {
+  changes: SpecChangeEntry[];
+  applied: boolean;
+  revision: number;
+  integration: Integration;
+}
+
+// Where a spec version came from
+export type SpecSource =
+  | { type: "url"; url: string }
+  | { type: "file"; path: string }
+  | { type: "content" };
+
+// One version in an integration's spec history
+export interface SpecHistoryEntry {
+  revision: number;
+  source: SpecSource;
+  specVersion?: string;
+  specHash?: string;
+  operationCount: number;
+  syncedAt?: string;
+  replacedAt?: string;
+}
+
 // Credential
 export interface Credential {
   id: string;
   provider: string;
   name: string;
-  credentialType: "api_key" | "oauth2_token" | "basic_auth";
+  credentialType:
+    | "api_key"
+    | "oauth2_token"
+    | "oauth2_client_credentials"
+    | "basic_auth"
+    | "multi_field"
+    | "client_certificate"
+    | "external";
   prefix?: string;
+  username?: string;
+  fieldNames: string[];
+  grantedScopes?: string[];
   integrationId?: string;
   lastUsedAt?: string;
+  usage: CredentialUsage;
   createdAt: string;
 }
 
+// Request counters for a credential
+export interface CredentialUsage {
+  useCount: number;
+  errorCount: number;
+  errorRate: number;
+  lastIntegration?: string;
+  lastOperation?: string;
+  lastStatus?: number;
+}
+
+// Secret found in a dotenv file or the environment
+export interface ImportCandidate {
+  variable: string;
+  masked: string;
+  source: string;
+  provider?: string;
+  integration?: string;
+}
+
+// Credential audit log entry
+export interface AuditEntry {
+  timestamp: string;
+  credentialId: string;
+  action: "decrypt" | "refresh" | "mint_token" | "cached_token";
+  requester?: string;
+}
+
 // Server status
 export interface ServerStatus {
   running: boolean;
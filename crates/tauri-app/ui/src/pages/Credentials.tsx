@@ -150,7 +150,7 @@ export default function CredentialsPage() {
         <div className="space-y-4">
           {Object.entries(credentialsByProvider).map(([provider, creds]) => {
             const catalogEntry = INTEGRATION_CATALOG.find((i) => i.id === provider);
-            const integration = integrations.find((i) => i.key === provider);
+            const integration = integrations.find((i) => i.provider === provider);
 
             return (
               <div
@@ -320,11 +320,11 @@ function AddCredentialModal({ integrations, onClose, onComplete }: AddCredential
                     key={int.key}
                     type="button"
                     onClick={() => {
-                      setProvider(int.key);
+                      setProvider(int.provider);
                       setName(`${int.name} API Key`);
                     }}
                     className={`px-3 py-1.5 rounded-lg text-sm transition-colors ${
-                      provider === int.key
+                      provider === int.provider
                         ? "bg-accent text-white"
                         : "bg-gray-800 text-gray-400 hover:text-white"
                     }`}
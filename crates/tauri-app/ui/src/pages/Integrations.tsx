@@ -124,7 +124,16 @@ export default function IntegrationsPage() {
   const handleSync = async (key: string) => {
     setSyncingId(key);
     try {
-      await syncIntegration(key);
+      const report = await syncIntegration(key);
+      if (!report.applied) {
+        const breaking = report.changes
+          .filter((c) => c.breaking)
+          .map((c) => `- ${c.description}`)
+          .join("\n");
+        if (window.confirm(`This sync has breaking changes:\n${breaking}\n\nApply anyway?`)) {
+          await syncIntegration(key, true);
+        }
+      }
       await loadInstalledIntegrations();
     } catch (error) {
       console.error("Failed to sync integration:", error);
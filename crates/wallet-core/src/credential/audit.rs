@@ -0,0 +1,183 @@
+//! Append-only encrypted audit log of credential access
+//!
+//! Each line of the log file is one JSON entry encrypted with the master key,
+//! so entries can be appended without rewriting (or decrypting) the file.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::crypto::{decrypt_string, encrypt_string, MasterKey};
+use crate::error::Result;
+
+tokio::task_local! {
+    static REQUESTER: String;
+}
+
+/// Run a future with credential access attributed to a session or client
+///
+/// Access outside of this scope is recorded without a requester.
+pub async fn with_requester<F: Future>(requester: impl Into<String>, future: F) -> F::Output {
+    REQUESTER.scope(requester.into(), future).await
+}
+
+/// The session or client the current task is acting for
+fn current_requester() -> Option<String> {
+    REQUESTER.try_with(|requester| requester.clone()).ok()
+}
+
+/// What happened to a credential
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A stored secret was decrypted
+    Decrypt,
+    /// An OAuth2 access token was refreshed
+    Refresh,
+    /// A client credentials token was minted from the stored client secret
+    MintToken,
+    /// A cached client credentials token or external secret was handed out
+    CachedToken,
+    /// An external secret was fetched from its store
+    Resolve,
+}
+
+/// One audit log entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the access happened
+    pub timestamp: DateTime<Utc>,
+
+    /// Credential that was accessed
+    pub credential_id: Uuid,
+
+    /// What happened
+    pub action: AuditAction,
+
+    /// Session or client that asked for the credential (if known)
+    pub requester: Option<String>,
+}
+
+impl AuditEntry {
+    /// Create an entry for the current task's requester
+    pub fn new(credential_id: Uuid, action: AuditAction) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            credential_id,
+            action,
+            requester: current_requester(),
+        }
+    }
+}
+
+/// Append-only audit log file
+pub struct AuditLog {
+    /// Path of the log file
+    path: PathBuf,
+    /// Serializes appends so lines are never interleaved
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    /// Create an audit log stored at the given path
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Append an entry
+    pub async fn append(&self, entry: &AuditEntry, key: &MasterKey) -> Result<()> {
+        let line = encrypt_string(&serde_json::to_string(entry)?, key)?;
+
+        let _guard = self.lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(format!("{}\n", line).as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Read all entries, oldest first
+    ///
+    /// Lines that cannot be decrypted with the given key are skipped.
+    pub async fn read(&self, key: &MasterKey) -> Result<Vec<AuditEntry>> {
+        let contents = match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            match decrypt_string(line, key)
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+            {
+                Some(entry) => entries.push(entry),
+                None => warn!("Skipping unreadable audit log line {}", index + 1),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Delete the log file (only when the whole wallet is reset)
+    pub(crate) async fn clear(&self) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        match tokio::fs::remove_file(&self.path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{derive_key, generate_salt};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_append_and_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = AuditLog::new(temp_dir.path().join("audit.log"));
+        let key = derive_key("test-password", &generate_salt(), None).unwrap();
+        let id = Uuid::new_v4();
+
+        assert!(log.read(&key).await.unwrap().is_empty());
+
+        log.append(&AuditEntry::new(id, AuditAction::Decrypt), &key)
+            .await
+            .unwrap();
+        with_requester("claude-desktop 1.0", async {
+            log.append(&AuditEntry::new(id, AuditAction::Refresh), &key)
+                .await
+                .unwrap();
+        })
+        .await;
+
+        let entries = log.read(&key).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, AuditAction::Decrypt);
+        assert_eq!(entries[0].requester, None);
+        assert_eq!(entries[1].requester.as_deref(), Some("claude-desktop 1.0"));
+
+        // Entries are not readable without the key
+        let contents = std::fs::read_to_string(temp_dir.path().join("audit.log")).unwrap();
+        assert!(!contents.contains(&id.to_string()));
+        let other = derive_key("other-password", &generate_salt(), None).unwrap();
+        assert!(log.read(&other).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,453 @@
+//! OAuth2 authorization-code login with PKCE (RFC 7636)
+//!
+//! The flow binds a loopback listener on 127.0.0.1, hands back an
+//! authorization URL for the user's browser, waits for the redirect and
+//! exchanges the code for tokens.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use openapi_parser::AuthScheme;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info};
+use url::Url;
+
+use super::oauth2::{OAuth2Client, TokenResponse};
+use crate::crypto::SecretString;
+use crate::error::{Result, WalletError};
+
+/// Path the loopback listener expects the redirect on
+const CALLBACK_PATH: &str = "/callback";
+
+/// Largest redirect request the loopback listener will read
+const MAX_CALLBACK_REQUEST: usize = 16 * 1024;
+
+/// PKCE code verifier and its S256 challenge
+pub struct Pkce {
+    verifier: SecretString,
+    challenge: String,
+}
+
+impl Pkce {
+    /// Generate a random verifier (43 URL-safe characters) and its challenge
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self::from_verifier(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Build from an existing verifier
+    pub fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier: SecretString::new(verifier),
+            challenge,
+        }
+    }
+
+    /// The code verifier sent with the token request
+    pub fn verifier(&self) -> &str {
+        self.verifier.expose()
+    }
+
+    /// The S256 code challenge sent with the authorization request
+    pub fn challenge(&self) -> &str {
+        &self.challenge
+    }
+}
+
+/// Configuration for an authorization-code login
+#[derive(Debug, Clone)]
+pub struct AuthorizationCodeFlow {
+    /// Authorization endpoint
+    pub authorization_url: String,
+    /// Token endpoint
+    pub token_url: String,
+    /// Scopes to request
+    pub scopes: Vec<String>,
+    /// OAuth2 client ID
+    pub client_id: String,
+    /// OAuth2 client secret (public clients have none)
+    pub client_secret: Option<SecretString>,
+    /// Loopback port for the redirect (0 picks a free port)
+    pub redirect_port: u16,
+}
+
+impl AuthorizationCodeFlow {
+    /// Build a flow from an integration's detected OAuth2 scheme
+    pub fn from_auth_scheme(
+        scheme: &AuthScheme,
+        client_id: &str,
+        client_secret: Option<&str>,
+    ) -> Result<Self> {
+        let AuthScheme::OAuth2 {
+            authorization_url,
+            token_url,
+            scopes,
+        } = scheme
+        else {
+            return Err(WalletError::OAuth2Error(
+                "Integration does not use OAuth2".to_string(),
+            ));
+        };
+
+        let authorization_url = authorization_url.clone().ok_or_else(|| {
+            WalletError::OAuth2Error("Spec defines no authorization URL".to_string())
+        })?;
+        let token_url = token_url
+            .clone()
+            .ok_or_else(|| WalletError::OAuth2Error("Spec defines no token URL".to_string()))?;
+
+        Ok(Self {
+            authorization_url,
+            token_url,
+            scopes: scopes.clone(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.map(|s| SecretString::new(s.to_string())),
+            redirect_port: 0,
+        })
+    }
+
+    /// Use a fixed loopback port (for providers that require an exact redirect URI)
+    pub fn with_redirect_port(mut self, port: u16) -> Self {
+        self.redirect_port = port;
+        self
+    }
+
+    /// Bind the loopback listener and build the authorization URL
+    pub async fn start(self) -> Result<PendingAuthorization> {
+        let listener = TcpListener::bind(("127.0.0.1", self.redirect_port)).await?;
+        let port = listener.local_addr()?.port();
+        let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+        let pkce = Pkce::generate();
+        let state = random_state();
+
+        let mut url = Url::parse(&self.authorization_url)
+            .map_err(|e| WalletError::OAuth2Error(format!("Invalid authorization URL: {}", e)))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &self.client_id)
+                .append_pair("redirect_uri", &redirect_uri)
+                .append_pair("state", &state)
+                .append_pair("code_challenge", pkce.challenge())
+                .append_pair("code_challenge_method", "S256");
+            if !self.scopes.is_empty() {
+                query.append_pair("scope", &self.scopes.join(" "));
+            }
+        }
+
+        info!("Waiting for OAuth2 redirect on {}", redirect_uri);
+
+        Ok(PendingAuthorization {
+            flow: self,
+            listener,
+            pkce,
+            state,
+            redirect_uri,
+            authorization_url: url.to_string(),
+        })
+    }
+}
+
+/// An authorization in progress, waiting for the browser redirect
+pub struct PendingAuthorization {
+    flow: AuthorizationCodeFlow,
+    listener: TcpListener,
+    pkce: Pkce,
+    state: String,
+    redirect_uri: String,
+    authorization_url: String,
+}
+
+impl PendingAuthorization {
+    /// URL to open in the user's browser
+    pub fn authorization_url(&self) -> &str {
+        &self.authorization_url
+    }
+
+    /// Loopback redirect URI registered for this login
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// The flow configuration this login was started with
+    pub fn flow(&self) -> &AuthorizationCodeFlow {
+        &self.flow
+    }
+
+    /// Wait for the redirect and exchange the authorization code for tokens
+    pub async fn wait_for_tokens(
+        &self,
+        client: &OAuth2Client,
+        timeout: Duration,
+    ) -> Result<TokenResponse> {
+        let code = tokio::time::timeout(timeout, self.accept_callback())
+            .await
+            .map_err(|_| {
+                WalletError::OAuth2Error("Timed out waiting for authorization".to_string())
+            })??;
+
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.expose()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("client_id", self.flow.client_id.as_str()),
+            ("code_verifier", self.pkce.verifier()),
+        ];
+        if let Some(secret) = &self.flow.client_secret {
+            params.push(("client_secret", secret.expose()));
+        }
+
+        client.request_token(&self.flow.token_url, &params).await
+    }
+
+    /// Accept redirect requests until one carries a code or an error
+    async fn accept_callback(&self) -> Result<SecretString> {
+        loop {
+            let (mut stream, _) = self.listener.accept().await?;
+
+            let target = match read_request_target(&mut stream).await {
+                Ok(target) => target,
+                Err(e) => {
+                    debug!("Ignoring malformed loopback request: {}", e);
+                    continue;
+                }
+            };
+
+            let url = match Url::parse(&format!("http://127.0.0.1{}", target)) {
+                Ok(url) if url.path() == CALLBACK_PATH => url,
+                _ => {
+                    // Browsers also ask for /favicon.ico and the like
+                    respond(&mut stream, "404 Not Found", "Not found").await;
+                    continue;
+                }
+            };
+
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(k, _)| k == name)
+                    .map(|(_, v)| v.into_owned())
+            };
+
+            if param("state").as_deref() != Some(self.state.as_str()) {
+                respond(
+                    &mut stream,
+                    "400 Bad Request",
+                    "Login failed: state mismatch.",
+                )
+                .await;
+                return Err(WalletError::OAuth2Error(
+                    "Authorization state mismatch".to_string(),
+                ));
+            }
+
+            if let Some(error) = param("error") {
+                respond(&mut stream, "400 Bad Request", "Login was not authorized.").await;
+                return Err(WalletError::OAuth2Error(format!(
+                    "Authorization denied: {}",
+                    error
+                )));
+            }
+
+            match param("code") {
+                Some(code) => {
+                    respond(
+                        &mut stream,
+                        "200 OK",
+                        "Login complete. You can close this window and return to MCP Wallet.",
+                    )
+                    .await;
+                    return Ok(SecretString::new(code));
+                }
+                None => {
+                    respond(
+                        &mut stream,
+                        "400 Bad Request",
+                        "Missing authorization code.",
+                    )
+                    .await;
+                    return Err(WalletError::OAuth2Error(
+                        "Redirect carried no authorization code".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Generate an unguessable `state` value
+fn random_state() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Read an HTTP request head and return its request target
+async fn read_request_target(stream: &mut TcpStream) -> Result<String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 || buf.len() + n > MAX_CALLBACK_REQUEST {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) if target.starts_with('/') => Ok(target.to_string()),
+        _ => Err(WalletError::OAuth2Error(
+            "Unexpected loopback request".to_string(),
+        )),
+    }
+}
+
+/// Write a minimal HTML response to the browser
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!doctype html><html><body><p>{}</p></body></html>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Form, Json, Router};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_pkce_rfc7636_vector() {
+        // RFC 7636 Appendix B
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(
+            pkce.challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_from_auth_scheme_requires_oauth2() {
+        let result = AuthorizationCodeFlow::from_auth_scheme(
+            &AuthScheme::Bearer { format: None },
+            "id",
+            None,
+        );
+        assert!(matches!(result, Err(WalletError::OAuth2Error(_))));
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow() {
+        let received: Arc<Mutex<HashMap<String, String>>> = Arc::default();
+        let captured = received.clone();
+        let app = Router::new().route(
+            "/token",
+            post(
+                move |Form(params): Form<HashMap<String, String>>| async move {
+                    *captured.lock().unwrap() = params;
+                    Json(serde_json::json!({
+                        "access_token": "at-1",
+                        "refresh_token": "rt-1",
+                        "expires_in": 3600
+                    }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let scheme = AuthScheme::OAuth2 {
+            authorization_url: Some("https://auth.example.com/authorize".to_string()),
+            token_url: Some(format!("{}/token", base)),
+            scopes: vec!["read".to_string(), "write".to_string()],
+        };
+        let pending = AuthorizationCodeFlow::from_auth_scheme(&scheme, "client-1", None)
+            .unwrap()
+            .start()
+            .await
+            .unwrap();
+
+        let auth_url = Url::parse(pending.authorization_url()).unwrap();
+        let query: HashMap<String, String> = auth_url.query_pairs().into_owned().collect();
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], "client-1");
+        assert_eq!(query["scope"], "read write");
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(query["redirect_uri"], pending.redirect_uri());
+
+        // Play the browser: hit an unrelated path, then follow the redirect
+        let redirect = format!(
+            "{}?code=code-1&state={}",
+            pending.redirect_uri(),
+            query["state"]
+        );
+        let favicon = pending
+            .redirect_uri()
+            .replace(CALLBACK_PATH, "/favicon.ico");
+        let browser = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let status = client.get(favicon).send().await.unwrap().status();
+            assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+            client.get(redirect).send().await.unwrap().status()
+        });
+
+        let tokens = pending
+            .wait_for_tokens(&OAuth2Client::new(), Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(tokens.access_token, "at-1");
+        assert_eq!(browser.await.unwrap(), reqwest::StatusCode::OK);
+
+        let params = received.lock().unwrap().clone();
+        assert_eq!(params["grant_type"], "authorization_code");
+        assert_eq!(params["code"], "code-1");
+        assert_eq!(params["redirect_uri"], pending.redirect_uri());
+        let verifier = Pkce::from_verifier(params["code_verifier"].clone());
+        assert_eq!(verifier.challenge(), query["code_challenge"]);
+    }
+
+    #[tokio::test]
+    async fn test_state_mismatch_rejected() {
+        let scheme = AuthScheme::OAuth2 {
+            authorization_url: Some("https://auth.example.com/authorize".to_string()),
+            token_url: Some("https://auth.example.com/token".to_string()),
+            scopes: vec![],
+        };
+        let pending = AuthorizationCodeFlow::from_auth_scheme(&scheme, "client-1", None)
+            .unwrap()
+            .start()
+            .await
+            .unwrap();
+
+        let redirect = format!("{}?code=code-1&state=forged", pending.redirect_uri());
+        tokio::spawn(async move {
+            let _ = reqwest::get(redirect).await;
+        });
+
+        let result = pending
+            .wait_for_tokens(&OAuth2Client::new(), Duration::from_secs(10))
+            .await;
+        assert!(matches!(result, Err(WalletError::OAuth2Error(_))));
+    }
+}
@@ -0,0 +1,291 @@
+//! Secrets held in external stores
+//!
+//! An external credential stores only a reference (a `pass` entry, a command
+//! that prints the secret, or a HashiCorp Vault KV path). The secret itself
+//! is fetched when the credential is used and cached in memory for a TTL.
+
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::error::{Result, WalletError};
+
+/// Timeout for commands and Vault requests
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Environment variable holding the Vault token when none is stored
+const VAULT_TOKEN_VAR: &str = "VAULT_TOKEN";
+
+/// Where an external secret is held
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExternalSource {
+    /// Entry of the `pass` password store (the first line is the secret)
+    Pass { entry: String },
+    /// Command that prints the secret on stdout (run without a shell)
+    Command { program: String, args: Vec<String> },
+    /// Field of a HashiCorp Vault KV version 2 secret
+    Vault {
+        /// Vault server address (e.g., "https://vault.example.com:8200")
+        address: String,
+        /// Mount point of the KV engine (e.g., "secret")
+        mount: String,
+        /// Secret path below the mount
+        path: String,
+        /// Field of the secret holding the value
+        field: String,
+    },
+}
+
+impl ExternalSource {
+    /// Human-readable reference (safe to display)
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Pass { entry } => format!("pass:{}", entry),
+            Self::Command { program, .. } => format!("command:{}", program),
+            Self::Vault {
+                mount, path, field, ..
+            } => format!("vault:{}/{}#{}", mount, path, field),
+        }
+    }
+}
+
+/// External secret reference (stored alongside an external credential)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalSecret {
+    /// Where the secret is held
+    pub source: ExternalSource,
+
+    /// How long a fetched secret is cached in memory (0 disables caching)
+    pub ttl_secs: u64,
+}
+
+/// Fetches secrets from external stores
+#[derive(Clone)]
+pub struct ExternalResolver {
+    http: reqwest::Client,
+}
+
+impl ExternalResolver {
+    /// Create a new resolver
+    pub fn new() -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(RESOLVE_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { http }
+    }
+
+    /// Fetch a secret
+    ///
+    /// `vault_token` is used for Vault sources, falling back to `VAULT_TOKEN`.
+    pub async fn resolve(
+        &self,
+        source: &ExternalSource,
+        vault_token: Option<&str>,
+    ) -> Result<String> {
+        debug!("Resolving external secret {}", source.describe());
+        match source {
+            ExternalSource::Pass { entry } => {
+                // `--` keeps an entry starting with a dash from being read as an option
+                let args = ["show".to_string(), "--".to_string(), entry.clone()];
+                let output = run("pass", &args).await?;
+                Ok(output.lines().next().unwrap_or_default().to_string())
+            }
+            ExternalSource::Command { program, args } => run(program, args).await,
+            ExternalSource::Vault {
+                address,
+                mount,
+                path,
+                field,
+            } => {
+                let token = match vault_token {
+                    Some(token) => token.to_string(),
+                    None => std::env::var(VAULT_TOKEN_VAR).map_err(|_| {
+                        WalletError::ExternalSecretError("No Vault token available".to_string())
+                    })?,
+                };
+                self.read_vault(address, mount, path, field, &token).await
+            }
+        }
+    }
+
+    /// Read a field of a KV version 2 secret
+    async fn read_vault(
+        &self,
+        address: &str,
+        mount: &str,
+        path: &str,
+        field: &str,
+        token: &str,
+    ) -> Result<String> {
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            address.trim_end_matches('/'),
+            mount.trim_matches('/'),
+            path.trim_start_matches('/')
+        );
+
+        let response = self
+            .http
+            .get(&url)
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .map_err(|e| {
+                WalletError::ExternalSecretError(format!("Vault request failed: {}", e))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(WalletError::ExternalSecretError(format!(
+                "Vault returned {} for {}/{}",
+                status, mount, path
+            )));
+        }
+
+        let body: serde_json::Value = response.json().await.map_err(|e| {
+            WalletError::ExternalSecretError(format!("Invalid Vault response: {}", e))
+        })?;
+        body.pointer("/data/data")
+            .and_then(|data| data.get(field))
+            .and_then(|value| value.as_str())
+            .map(String::from)
+            .ok_or_else(|| {
+                WalletError::ExternalSecretError(format!(
+                    "Vault secret {}/{} has no field {}",
+                    mount, path, field
+                ))
+            })
+    }
+}
+
+impl Default for ExternalResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a command and return its stdout without the trailing newline
+async fn run(program: &str, args: &[String]) -> Result<String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(RESOLVE_TIMEOUT, output)
+        .await
+        .map_err(|_| WalletError::ExternalSecretError(format!("{} timed out", program)))?
+        .map_err(|e| {
+            WalletError::ExternalSecretError(format!("Failed to run {}: {}", program, e))
+        })?;
+
+    if !output.status.success() {
+        return Err(WalletError::ExternalSecretError(format!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8(output.stdout).map_err(|_| {
+        WalletError::ExternalSecretError(format!("{} printed a non UTF-8 secret", program))
+    })?;
+    let secret = stdout.trim_end_matches(['\n', '\r']).to_string();
+    if secret.is_empty() {
+        return Err(WalletError::ExternalSecretError(format!(
+            "{} printed an empty secret",
+            program
+        )));
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path, http::HeaderMap, routing::get, Json, Router};
+
+    fn command(script: &str) -> ExternalSource {
+        ExternalSource::Command {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_command() {
+        let resolver = ExternalResolver::new();
+
+        let secret = resolver
+            .resolve(&command("printf 'from-command\\n'"), None)
+            .await
+            .unwrap();
+        assert_eq!(secret, "from-command");
+
+        let err = resolver
+            .resolve(&command("echo locked >&2; exit 1"), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("locked"));
+
+        assert!(resolver.resolve(&command("true"), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_vault() {
+        async fn secret(
+            Path(path): Path<String>,
+            headers: HeaderMap,
+        ) -> (axum::http::StatusCode, Json<serde_json::Value>) {
+            if headers.get("x-vault-token").and_then(|v| v.to_str().ok()) != Some("s.root") {
+                return (
+                    axum::http::StatusCode::FORBIDDEN,
+                    Json(serde_json::json!({"errors": ["permission denied"]})),
+                );
+            }
+            (
+                axum::http::StatusCode::OK,
+                Json(serde_json::json!({
+                    "data": {"data": {"api_key": format!("key-for-{}", path)}, "metadata": {"version": 3}}
+                })),
+            )
+        }
+
+        let app = Router::new().route("/v1/secret/data/*path", get(secret));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let source = |field: &str| ExternalSource::Vault {
+            address: address.clone(),
+            mount: "secret".to_string(),
+            path: "team/openai".to_string(),
+            field: field.to_string(),
+        };
+        let resolver = ExternalResolver::new();
+
+        let secret = resolver
+            .resolve(&source("api_key"), Some("s.root"))
+            .await
+            .unwrap();
+        assert_eq!(secret, "key-for-team/openai");
+
+        let err = resolver
+            .resolve(&source("api_key"), Some("s.wrong"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("403"));
+
+        let err = resolver
+            .resolve(&source("missing"), Some("s.root"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no field missing"));
+    }
+}
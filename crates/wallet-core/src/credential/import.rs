@@ -0,0 +1,374 @@
+//! Import credentials from dotenv files and environment variables
+//!
+//! Secrets are found by variable name (e.g., `OPENAI_API_KEY`) or by a
+//! well-known key prefix, matched to installed integrations, and can then be
+//! stored with `CredentialManager::import` and scrubbed from the source file.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use super::detect::suggest_provider;
+use crate::crypto::SecretString;
+use crate::error::Result;
+use crate::integration::Integration;
+
+/// Variable name suffixes that mark a value as a secret
+const SECRET_SUFFIXES: &[&str] = &["_API_KEY", "_KEY", "_TOKEN", "_SECRET", "_PASSWORD", "_PAT"];
+
+/// Placeholder written in place of a commented-out secret
+const SCRUBBED_PLACEHOLDER: &str = "<moved to MCP Wallet>";
+
+/// Where an imported secret came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    /// A line of a dotenv file or shell profile (1-based)
+    DotEnv { path: PathBuf, line: usize },
+    /// A variable of the current process environment
+    Environment,
+}
+
+/// A secret found in a dotenv file or the environment
+#[derive(Debug, Clone)]
+pub struct ImportCandidate {
+    /// Variable name (e.g., "OPENAI_API_KEY")
+    pub variable: String,
+
+    /// Secret value
+    pub value: SecretString,
+
+    /// Where the secret was found
+    pub source: ImportSource,
+
+    /// Suggested provider (from the key prefix or variable name)
+    pub provider: Option<String>,
+
+    /// Key of the installed integration the secret probably belongs to
+    pub integration: Option<String>,
+}
+
+impl ImportCandidate {
+    /// Create a candidate and suggest its provider
+    fn new(variable: &str, value: String, source: ImportSource) -> Self {
+        let provider = suggest_provider(&value)
+            .map(String::from)
+            .or_else(|| provider_from_variable(variable));
+
+        Self {
+            variable: variable.to_string(),
+            value: SecretString::new(value),
+            source,
+            provider,
+            integration: None,
+        }
+    }
+
+    /// Suggest the installed integration this secret belongs to
+    ///
+    /// An integration matches when its key equals the suggested provider or
+    /// prefixes the variable name (e.g., `github` for `GITHUB_TOKEN`).
+    pub fn match_integration(&mut self, integrations: &[Integration]) {
+        let variable = self.variable.to_lowercase();
+        self.integration = integrations
+            .iter()
+            .find(|i| self.provider.as_deref() == Some(i.key.as_str()))
+            .or_else(|| {
+                integrations
+                    .iter()
+                    .find(|i| variable.starts_with(&format!("{}_", i.key.to_lowercase())))
+            })
+            .map(|i| i.key.clone());
+    }
+}
+
+/// How to remove imported secrets from a dotenv file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrubAction {
+    /// Delete the lines
+    Remove,
+    /// Replace the lines with a comment naming the variable (the value is dropped)
+    CommentOut,
+}
+
+/// Find secrets in a dotenv file or shell profile
+pub async fn parse_dotenv(path: &Path) -> Result<Vec<ImportCandidate>> {
+    let contents = tokio::fs::read_to_string(path).await?;
+
+    let candidates: Vec<ImportCandidate> = contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (variable, value) = parse_line(line)?;
+            is_secret(variable, &value).then(|| {
+                ImportCandidate::new(
+                    variable,
+                    value,
+                    ImportSource::DotEnv {
+                        path: path.to_path_buf(),
+                        line: index + 1,
+                    },
+                )
+            })
+        })
+        .collect();
+
+    debug!("Found {} secrets in {:?}", candidates.len(), path);
+    Ok(candidates)
+}
+
+/// Read the named environment variables (unset or empty ones are skipped)
+pub fn from_environment(variables: &[&str]) -> Vec<ImportCandidate> {
+    variables
+        .iter()
+        .filter_map(|variable| {
+            let value = std::env::var(variable).ok().filter(|v| !v.is_empty())?;
+            Some(ImportCandidate::new(
+                variable,
+                value,
+                ImportSource::Environment,
+            ))
+        })
+        .collect()
+}
+
+/// Remove or comment out the lines defining the given variables
+///
+/// The new contents are written to a temporary file next to the original,
+/// which then replaces it, so the file is never left half written. The
+/// original's permissions are kept. Returns the number of lines changed.
+pub async fn scrub_dotenv(path: &Path, variables: &[String], action: ScrubAction) -> Result<usize> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    let variables: HashSet<&str> = variables.iter().map(String::as_str).collect();
+
+    let mut scrubbed = 0;
+    let mut output = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        match parse_line(line).filter(|(variable, _)| variables.contains(variable)) {
+            Some((variable, _)) => {
+                scrubbed += 1;
+                if action == ScrubAction::CommentOut {
+                    output.push_str(&format!("# {}={}\n", variable, SCRUBBED_PLACEHOLDER));
+                }
+            }
+            None => {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+    if !contents.ends_with('\n') && output.ends_with('\n') {
+        output.pop();
+    }
+
+    // Replace the file a symlink points to, not the symlink
+    let path = tokio::fs::canonicalize(path).await?;
+    let permissions = tokio::fs::metadata(&path).await?.permissions();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+    let replaced = async {
+        tokio::fs::write(&temp_path, output).await?;
+        tokio::fs::set_permissions(&temp_path, permissions).await?;
+        tokio::fs::rename(&temp_path, &path).await
+    }
+    .await;
+    if replaced.is_err() {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+    }
+    replaced?;
+
+    info!("Scrubbed {} secrets from {:?}", scrubbed, path);
+    Ok(scrubbed)
+}
+
+/// Parse a `[export] NAME=value` line into its name and unquoted value
+///
+/// Comments, blank lines and empty values yield `None`. Multi-line quoted
+/// values are not supported.
+fn parse_line(line: &str) -> Option<(&str, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+
+    let (variable, value) = line.split_once('=')?;
+    let variable = variable.trim();
+    let mut chars = variable.chars();
+    if !chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+
+    let value = value.trim();
+    let value = if let Some(rest) = value.strip_prefix('"') {
+        let mut unescaped = String::new();
+        let mut chars = rest.chars();
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => match chars.next()? {
+                    'n' => unescaped.push('\n'),
+                    other => unescaped.push(other),
+                },
+                c => unescaped.push(c),
+            }
+        }
+        unescaped
+    } else if let Some(rest) = value.strip_prefix('\'') {
+        rest.split_once('\'')?.0.to_string()
+    } else {
+        value
+            .split_once(" #")
+            .map_or(value, |(value, _)| value)
+            .trim_end()
+            .to_string()
+    };
+
+    (!value.is_empty()).then_some((variable, value))
+}
+
+/// Check whether a variable looks like it holds a secret
+fn is_secret(variable: &str, value: &str) -> bool {
+    let upper = variable.to_ascii_uppercase();
+    SECRET_SUFFIXES.iter().any(|suffix| upper.ends_with(suffix))
+        || suggest_provider(value).is_some()
+}
+
+/// Guess the provider from a variable name (e.g., "openai" for `OPENAI_API_KEY`)
+fn provider_from_variable(variable: &str) -> Option<String> {
+    let upper = variable.to_ascii_uppercase();
+    let stem = SECRET_SUFFIXES
+        .iter()
+        .find_map(|suffix| upper.strip_suffix(suffix))?;
+    stem.split('_')
+        .find(|part| !part.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const DOTENV: &str = r#"# Local development
+export OPENAI_API_KEY="sk-proj-abc123"
+GITHUB_TOKEN='ghp_16C7e42F292c'
+DATABASE_URL=postgres://localhost/dev
+STRIPE_SECRET_KEY=sk_live_51H # live key
+SLACK=xoxb-1234-5678
+EMPTY_TOKEN=
+"#;
+
+    #[tokio::test]
+    async fn test_parse_dotenv() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".env");
+        std::fs::write(&path, DOTENV).unwrap();
+
+        let candidates = parse_dotenv(&path).await.unwrap();
+        let found: Vec<(&str, &str, Option<&str>)> = candidates
+            .iter()
+            .map(|c| (c.variable.as_str(), c.value.expose(), c.provider.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("OPENAI_API_KEY", "sk-proj-abc123", Some("openai")),
+                ("GITHUB_TOKEN", "ghp_16C7e42F292c", Some("github")),
+                ("STRIPE_SECRET_KEY", "sk_live_51H", Some("stripe")),
+                ("SLACK", "xoxb-1234-5678", Some("slack")),
+            ]
+        );
+        assert_eq!(
+            candidates[1].source,
+            ImportSource::DotEnv {
+                path: path.clone(),
+                line: 3
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scrub_dotenv() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".env");
+        std::fs::write(&path, DOTENV).unwrap();
+
+        let variables = vec!["OPENAI_API_KEY".to_string(), "SLACK".to_string()];
+        let scrubbed = scrub_dotenv(&path, &variables, ScrubAction::CommentOut)
+            .await
+            .unwrap();
+        assert_eq!(scrubbed, 2);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("# OPENAI_API_KEY=<moved to MCP Wallet>\n"));
+        assert!(!contents.contains("sk-proj-abc123"));
+        assert!(!contents.contains("xoxb-"));
+        assert!(contents.contains("DATABASE_URL=postgres://localhost/dev\n"));
+
+        let variables = vec!["GITHUB_TOKEN".to_string()];
+        scrub_dotenv(&path, &variables, ScrubAction::Remove)
+            .await
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("GITHUB_TOKEN"));
+        assert!(contents.starts_with("# Local development\n"));
+
+        // The file is replaced whole, keeping its permissions
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+            let variables = vec!["DATABASE_URL".to_string()];
+            scrub_dotenv(&path, &variables, ScrubAction::Remove)
+                .await
+                .unwrap();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_from_environment() {
+        std::env::set_var("MCP_WALLET_IMPORT_TEST_TOKEN", "xoxb-env");
+        let candidates = from_environment(&["MCP_WALLET_IMPORT_TEST_TOKEN", "MCP_WALLET_UNSET"]);
+        std::env::remove_var("MCP_WALLET_IMPORT_TEST_TOKEN");
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].source, ImportSource::Environment);
+        assert_eq!(candidates[0].provider.as_deref(), Some("slack"));
+    }
+
+    #[test]
+    fn test_match_integration() {
+        let spec = openapi_parser::ParsedSpec {
+            title: "GitHub".to_string(),
+            description: None,
+            version: "1.0.0".to_string(),
+            servers: Vec::new(),
+            operations: Vec::new(),
+            security_schemes: Default::default(),
+            global_security: Vec::new(),
+        };
+        let github = Integration::from_spec("github".to_string(), &spec);
+        let mut candidate = ImportCandidate::new(
+            "GH_PERSONAL",
+            "ghp_abc".to_string(),
+            ImportSource::Environment,
+        );
+        candidate.match_integration(std::slice::from_ref(&github));
+        assert_eq!(candidate.integration.as_deref(), Some("github"));
+
+        let mut candidate = ImportCandidate::new(
+            "GITHUB_ENTERPRISE_TOKEN",
+            "abc".to_string(),
+            ImportSource::Environment,
+        );
+        candidate.match_integration(&[github]);
+        assert_eq!(candidate.integration.as_deref(), Some("github"));
+    }
+}
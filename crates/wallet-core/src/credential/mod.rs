@@ -1,7 +1,18 @@
 //! Credential management for secure API key storage
 
+mod audit;
+mod authorization;
+mod detect;
+mod external;
+pub mod import;
 mod manager;
+mod oauth2;
 mod types;
 
-pub use manager::CredentialManager;
+pub use audit::{with_requester, AuditAction, AuditEntry, AuditLog};
+pub use authorization::{AuthorizationCodeFlow, PendingAuthorization, Pkce};
+pub use detect::suggest_provider;
+pub use external::{ExternalResolver, ExternalSecret, ExternalSource};
+pub use manager::{CredentialManager, FetchedToken, RefreshWait, TokenLookup, TokenRequest};
+pub use oauth2::{OAuth2Client, TokenResponse};
 pub use types::*;
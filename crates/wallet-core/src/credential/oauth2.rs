@@ -0,0 +1,153 @@
+//! OAuth2 token endpoint client
+//!
+//! Implements the token requests from RFC 6749 used by the wallet to keep
+//! OAuth2 credentials usable without manual token management.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::error::{Result, WalletError};
+
+/// Successful token endpoint response (RFC 6749 section 5.1)
+#[derive(Clone, Deserialize)]
+pub struct TokenResponse {
+    /// Access token issued by the authorization server
+    pub access_token: String,
+    /// Token type (usually "Bearer")
+    #[serde(default)]
+    pub token_type: Option<String>,
+    /// Refresh token (present when issued or rotated)
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Lifetime of the access token in seconds
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+    /// Space-separated scopes granted to the token
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+impl TokenResponse {
+    /// Absolute expiration time derived from `expires_in`
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_in
+            .map(|secs| Utc::now() + chrono::Duration::seconds(secs))
+    }
+
+    /// Granted scopes, if the server reported them
+    pub fn scopes(&self) -> Option<Vec<String>> {
+        self.scope
+            .as_ref()
+            .map(|scope| scope.split_whitespace().map(String::from).collect())
+    }
+}
+
+impl std::fmt::Debug for TokenResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenResponse")
+            .field("access_token", &"[REDACTED]")
+            .field("token_type", &self.token_type)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("expires_in", &self.expires_in)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+/// Error response from a token endpoint (RFC 6749 section 5.2)
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// HTTP client for OAuth2 token endpoints
+#[derive(Clone)]
+pub struct OAuth2Client {
+    http: reqwest::Client,
+}
+
+impl OAuth2Client {
+    /// Create a new OAuth2 client
+    pub fn new() -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { http }
+    }
+
+    /// Exchange a refresh token for a new access token
+    pub async fn refresh(
+        &self,
+        token_url: &str,
+        refresh_token: &str,
+        client_id: Option<&str>,
+        client_secret: Option<&str>,
+    ) -> Result<TokenResponse> {
+        let mut params = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ];
+        if let Some(client_id) = client_id {
+            params.push(("client_id", client_id));
+        }
+        if let Some(client_secret) = client_secret {
+            params.push(("client_secret", client_secret));
+        }
+
+        self.request_token(token_url, &params).await
+    }
+
+    /// POST a form-encoded token request and parse the response
+    pub async fn request_token(
+        &self,
+        token_url: &str,
+        params: &[(&str, &str)],
+    ) -> Result<TokenResponse> {
+        debug!("Requesting OAuth2 token from {}", token_url);
+
+        let response = self
+            .http
+            .post(token_url)
+            .header("Accept", "application/json")
+            .form(params)
+            .send()
+            .await
+            .map_err(|e| WalletError::OAuth2Error(format!("Token request failed: {}", e)))?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            WalletError::OAuth2Error(format!("Failed to read token response: {}", e))
+        })?;
+
+        if !status.is_success() {
+            // Only surface the error code; token endpoints may echo request data
+            let message = match serde_json::from_str::<TokenErrorResponse>(&body) {
+                Ok(err) => match err.error_description {
+                    Some(desc) => format!("{} ({})", err.error, desc),
+                    None => err.error,
+                },
+                Err(_) => format!("HTTP {}", status),
+            };
+            return Err(WalletError::OAuth2Error(format!(
+                "Token endpoint rejected request: {}",
+                message
+            )));
+        }
+
+        serde_json::from_str(&body)
+            .map_err(|e| WalletError::OAuth2Error(format!("Invalid token response: {}", e)))
+    }
+}
+
+impl Default for OAuth2Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,9 +2,12 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use super::external::{ExternalSecret, ExternalSource};
+
 /// Type of credential
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -16,6 +19,14 @@ pub enum CredentialType {
     OAuth2Token,
     /// Basic authentication (username:password)
     BasicAuth,
+    /// OAuth2 client credentials (tokens minted on demand)
+    OAuth2ClientCredentials,
+    /// Several named secrets sent together (e.g., key ID plus secret)
+    MultiField,
+    /// Mutual TLS client certificate and private key
+    ClientCertificate,
+    /// Reference to a secret held in an external store
+    External,
 }
 
 /// Credential metadata (safe to display)
@@ -36,16 +47,62 @@ pub struct Credential {
     /// First 8 characters for display (e.g., "sk-proj-...")
     pub prefix: Option<String>,
 
+    /// Username for basic auth (safe to display)
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Field names for multi-field credentials (values are encrypted)
+    #[serde(default)]
+    pub field_names: Vec<String>,
+
+    /// Scopes granted to an OAuth2 token (`None` if unknown)
+    #[serde(default)]
+    pub granted_scopes: Option<Vec<String>>,
+
     /// Associated integration ID (if any)
     pub integration_id: Option<Uuid>,
 
     /// Last time this credential was used
     pub last_used_at: Option<DateTime<Utc>>,
 
+    /// Request counters for this credential
+    #[serde(default)]
+    pub usage: CredentialUsage,
+
     /// Created timestamp
     pub created_at: DateTime<Utc>,
 }
 
+/// Request counters for a credential (safe to display)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CredentialUsage {
+    /// Number of requests made with this credential
+    pub use_count: u64,
+
+    /// Number of requests that failed or returned an HTTP error
+    pub error_count: u64,
+
+    /// Integration of the most recent request
+    pub last_integration: Option<String>,
+
+    /// Operation of the most recent request
+    pub last_operation: Option<String>,
+
+    /// HTTP status of the most recent request (`None` if it was not answered)
+    pub last_status: Option<u16>,
+}
+
+impl CredentialUsage {
+    /// Fraction of requests that failed (0.0 when unused)
+    pub fn error_rate(&self) -> f64 {
+        if self.use_count == 0 {
+            0.0
+        } else {
+            self.error_count as f64 / self.use_count as f64
+        }
+    }
+}
+
 impl Credential {
     /// Create a new API key credential
     pub fn new_api_key(provider: &str, name: &str, api_key: &str) -> Self {
@@ -61,8 +118,12 @@ impl Credential {
             name: name.to_string(),
             credential_type: CredentialType::ApiKey,
             prefix,
+            username: None,
+            field_names: Vec::new(),
+            granted_scopes: None,
             integration_id: None,
             last_used_at: None,
+            usage: CredentialUsage::default(),
             created_at: Utc::now(),
         }
     }
@@ -75,8 +136,105 @@ impl Credential {
             name: name.to_string(),
             credential_type: CredentialType::OAuth2Token,
             prefix: None,
+            username: None,
+            field_names: Vec::new(),
+            granted_scopes: None,
             integration_id: None,
             last_used_at: None,
+            usage: CredentialUsage::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create a new OAuth2 client credentials credential
+    pub fn new_oauth2_client_credentials(provider: &str, name: &str, client_id: &str) -> Self {
+        // The client ID is not secret, so show it in full
+        Self {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            name: name.to_string(),
+            credential_type: CredentialType::OAuth2ClientCredentials,
+            prefix: Some(client_id.to_string()),
+            username: None,
+            field_names: Vec::new(),
+            granted_scopes: None,
+            integration_id: None,
+            last_used_at: None,
+            usage: CredentialUsage::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create a new basic auth credential
+    pub fn new_basic_auth(provider: &str, name: &str, username: &str) -> Self {
+        // No prefix: the only secret part is the password
+        Self {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            name: name.to_string(),
+            credential_type: CredentialType::BasicAuth,
+            prefix: None,
+            username: Some(username.to_string()),
+            field_names: Vec::new(),
+            granted_scopes: None,
+            integration_id: None,
+            last_used_at: None,
+            usage: CredentialUsage::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create a new multi-field credential
+    pub fn new_multi_field(provider: &str, name: &str, field_names: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            name: name.to_string(),
+            credential_type: CredentialType::MultiField,
+            prefix: None,
+            username: None,
+            field_names,
+            granted_scopes: None,
+            integration_id: None,
+            last_used_at: None,
+            usage: CredentialUsage::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create a new mutual TLS client certificate credential
+    pub fn new_client_certificate(provider: &str, name: &str) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            name: name.to_string(),
+            credential_type: CredentialType::ClientCertificate,
+            prefix: None,
+            username: None,
+            field_names: Vec::new(),
+            granted_scopes: None,
+            integration_id: None,
+            last_used_at: None,
+            usage: CredentialUsage::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create a new external secret credential
+    pub fn new_external(provider: &str, name: &str, source: &ExternalSource) -> Self {
+        // The reference is not secret, so show it in place of a prefix
+        Self {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            name: name.to_string(),
+            credential_type: CredentialType::External,
+            prefix: Some(source.describe()),
+            username: None,
+            field_names: Vec::new(),
+            granted_scopes: None,
+            integration_id: None,
+            last_used_at: None,
+            usage: CredentialUsage::default(),
             created_at: Utc::now(),
         }
     }
@@ -90,8 +248,9 @@ pub struct DecryptedCredential {
 }
 
 impl DecryptedCredential {
-    /// Create a new decrypted credential
+    /// Create a new decrypted credential (registered for redaction)
     pub fn new(value: String) -> Self {
+        crate::redact::register_secret(&value);
         Self { value }
     }
 
@@ -114,6 +273,19 @@ impl std::fmt::Debug for DecryptedCredential {
     }
 }
 
+/// Decrypted mutual TLS client certificate material (PEM encoded)
+#[derive(Debug)]
+pub struct ClientCertificate {
+    /// Client certificate chain
+    pub certificate: DecryptedCredential,
+
+    /// Private key for the client certificate
+    pub private_key: DecryptedCredential,
+
+    /// CA bundle used to verify the server (in addition to the system roots)
+    pub ca_bundle: Option<DecryptedCredential>,
+}
+
 /// Stored credential (encrypted)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredCredential {
@@ -123,9 +295,53 @@ pub struct StoredCredential {
     /// Encrypted value (iv:tag:ciphertext format)
     pub encrypted_value: String,
 
+    /// Encrypted username (for basic auth; the password is `encrypted_value`)
+    #[serde(default)]
+    pub encrypted_username: Option<String>,
+
+    /// Individually encrypted named fields (for multi-field credentials)
+    #[serde(default)]
+    pub encrypted_fields: BTreeMap<String, String>,
+
     /// Encrypted refresh token (for OAuth2)
     pub encrypted_refresh_token: Option<String>,
 
     /// Token expiration (for OAuth2)
     pub expires_at: Option<DateTime<Utc>>,
+
+    /// OAuth2 client configuration used to refresh tokens (for OAuth2)
+    #[serde(default)]
+    pub oauth2_client: Option<OAuth2ClientConfig>,
+
+    /// Secret reference (for external credentials; `encrypted_value` holds
+    /// the Vault token, if any)
+    #[serde(default)]
+    pub external: Option<ExternalSecret>,
+}
+
+impl StoredCredential {
+    /// Check whether an OAuth2 access token expires within the given window
+    pub fn expires_within(&self, window: chrono::Duration) -> bool {
+        self.expires_at
+            .map(|expires_at| Utc::now() + window >= expires_at)
+            .unwrap_or(false)
+    }
+}
+
+/// OAuth2 client configuration (stored alongside an OAuth2 token)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OAuth2ClientConfig {
+    /// Token endpoint override (defaults to the integration's spec)
+    pub token_url: Option<String>,
+
+    /// OAuth2 client ID
+    pub client_id: Option<String>,
+
+    /// Encrypted client secret (iv:tag:ciphertext format)
+    pub encrypted_client_secret: Option<String>,
+
+    /// Multi-field credential holding the client ID and secret (used instead
+    /// of `client_id` and `encrypted_client_secret`)
+    #[serde(default)]
+    pub client_credential_id: Option<Uuid>,
 }
@@ -38,12 +38,18 @@ pub enum WalletError {
     #[error("Credential not found: {0}")]
     CredentialNotFound(String),
 
+    #[error("Invalid credential: {0}")]
+    InvalidCredential(String),
+
     #[error("Operation not found: {0}")]
     OperationNotFound(String),
 
     #[error("Invalid OpenAPI spec: {0}")]
     InvalidSpec(String),
 
+    #[error("Invalid integration key: {0}")]
+    InvalidIntegrationKey(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -61,4 +67,26 @@ pub enum WalletError {
 
     #[error("Crypto error: {0}")]
     CryptoError(String),
+
+    #[error("OAuth2 error: {0}")]
+    OAuth2Error(String),
+
+    #[error("Credential is missing required scopes: {0}")]
+    InsufficientScope(String),
+
+    #[error("External secret error: {0}")]
+    ExternalSecretError(String),
+
+    #[error("Connection failed: {0}")]
+    ConnectionError(String),
+
+    #[error("Integration is disabled: {0}")]
+    IntegrationDisabled(String),
+}
+
+impl WalletError {
+    /// Error message with secrets scrubbed, for display outside the process
+    pub fn redacted(&self) -> String {
+        crate::redact::redact(&self.to_string())
+    }
 }
@@ -0,0 +1,510 @@
+//! Structured diff between two versions of an integration's spec
+//!
+//! Operations are matched by namespace path (which determines the tool
+//! name). An operation that disappears while another appears with the same
+//! method and URL path is reported as renamed.
+
+use openapi_parser::{ApiOperation, AuthScheme};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One change between two versions of a spec
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpecChange {
+    /// A new operation (and tool) appeared
+    OperationAdded { operation: String },
+    /// An operation (and its tool) was removed
+    OperationRemoved { operation: String },
+    /// An operation's ID changed, so its tool name changed
+    OperationRenamed { from: String, to: String },
+    /// An operation moved to a different method or URL path
+    EndpointChanged {
+        operation: String,
+        from: String,
+        to: String,
+    },
+    /// A parameter was added
+    ParameterAdded {
+        operation: String,
+        parameter: String,
+        required: bool,
+    },
+    /// A parameter was removed
+    ParameterRemoved {
+        operation: String,
+        parameter: String,
+    },
+    /// An optional parameter became required
+    ParameterRequired {
+        operation: String,
+        parameter: String,
+    },
+    /// A required parameter became optional
+    ParameterOptional {
+        operation: String,
+        parameter: String,
+    },
+    /// A request body was added
+    BodyAdded { operation: String, required: bool },
+    /// The request body was removed
+    BodyRemoved { operation: String },
+    /// An optional request body became required
+    BodyRequired { operation: String },
+    /// A top-level request body field became required
+    BodyFieldRequired { operation: String, field: String },
+    /// An operation's security requirements changed
+    SecurityChanged { operation: String },
+    /// The integration's detected authentication scheme changed
+    AuthChanged {
+        from: Option<AuthScheme>,
+        to: Option<AuthScheme>,
+    },
+}
+
+impl SpecChange {
+    /// Whether existing tool calls may stop working after this change
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Self::OperationAdded { .. }
+            | Self::EndpointChanged { .. }
+            | Self::ParameterOptional { .. } => false,
+            Self::ParameterAdded { required, .. } | Self::BodyAdded { required, .. } => *required,
+            _ => true,
+        }
+    }
+
+    /// Whether this change makes a tool disappear
+    pub fn removes_tool(&self) -> bool {
+        matches!(
+            self,
+            Self::OperationRemoved { .. } | Self::OperationRenamed { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for SpecChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OperationAdded { operation } => write!(f, "Added {}", operation),
+            Self::OperationRemoved { operation } => write!(f, "Removed {}", operation),
+            Self::OperationRenamed { from, to } => write!(f, "Renamed {} to {}", from, to),
+            Self::EndpointChanged {
+                operation,
+                from,
+                to,
+            } => write!(f, "{} moved from {} to {}", operation, from, to),
+            Self::ParameterAdded {
+                operation,
+                parameter,
+                required,
+            } => write!(
+                f,
+                "{} has a new {} parameter {}",
+                operation,
+                if *required { "required" } else { "optional" },
+                parameter
+            ),
+            Self::ParameterRemoved {
+                operation,
+                parameter,
+            } => write!(f, "{} no longer accepts {}", operation, parameter),
+            Self::ParameterRequired {
+                operation,
+                parameter,
+            } => write!(f, "{} now requires {}", operation, parameter),
+            Self::ParameterOptional {
+                operation,
+                parameter,
+            } => write!(f, "{} no longer requires {}", operation, parameter),
+            Self::BodyAdded {
+                operation,
+                required,
+            } => write!(
+                f,
+                "{} accepts {} request body",
+                operation,
+                if *required {
+                    "a required"
+                } else {
+                    "an optional"
+                }
+            ),
+            Self::BodyRemoved { operation } => {
+                write!(f, "{} no longer accepts a request body", operation)
+            }
+            Self::BodyRequired { operation } => {
+                write!(f, "{} now requires a request body", operation)
+            }
+            Self::BodyFieldRequired { operation, field } => {
+                write!(f, "{} now requires body field {}", operation, field)
+            }
+            Self::SecurityChanged { operation } => {
+                write!(f, "{} has different security requirements", operation)
+            }
+            Self::AuthChanged { .. } => write!(f, "Authentication scheme changed"),
+        }
+    }
+}
+
+/// All changes between two versions of a spec
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpecDiff {
+    /// Changes in report order (auth, then operations by name)
+    pub changes: Vec<SpecChange>,
+}
+
+impl SpecDiff {
+    /// Compare the operations and detected auth scheme of two versions
+    pub fn compute(
+        old_operations: &[ApiOperation],
+        old_auth: Option<&AuthScheme>,
+        new_operations: &[ApiOperation],
+        new_auth: Option<&AuthScheme>,
+    ) -> Self {
+        let mut changes = Vec::new();
+
+        if old_auth != new_auth {
+            changes.push(SpecChange::AuthChanged {
+                from: old_auth.cloned(),
+                to: new_auth.cloned(),
+            });
+        }
+
+        let old: BTreeMap<&str, &ApiOperation> = old_operations
+            .iter()
+            .map(|op| (op.normalized_id.as_str(), op))
+            .collect();
+        let new: BTreeMap<&str, &ApiOperation> = new_operations
+            .iter()
+            .map(|op| (op.normalized_id.as_str(), op))
+            .collect();
+
+        let mut added: Vec<&str> = new
+            .keys()
+            .filter(|k| !old.contains_key(*k))
+            .copied()
+            .collect();
+        for (name, old_op) in &old {
+            match new.get(name) {
+                Some(new_op) => compare_operation(name, old_op, new_op, &mut changes),
+                None => {
+                    // Same endpoint under a new ID is a rename
+                    match added.iter().position(|candidate| {
+                        let new_op = new[candidate];
+                        new_op.method == old_op.method && new_op.path == old_op.path
+                    }) {
+                        Some(index) => {
+                            let to = added.remove(index);
+                            changes.push(SpecChange::OperationRenamed {
+                                from: name.to_string(),
+                                to: to.to_string(),
+                            });
+                            compare_operation(to, old_op, new[to], &mut changes);
+                        }
+                        None => changes.push(SpecChange::OperationRemoved {
+                            operation: name.to_string(),
+                        }),
+                    }
+                }
+            }
+        }
+        changes.extend(
+            added
+                .into_iter()
+                .map(|operation| SpecChange::OperationAdded {
+                    operation: operation.to_string(),
+                }),
+        );
+
+        Self { changes }
+    }
+
+    /// Whether the versions are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Whether any change may break existing tool calls
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(SpecChange::is_breaking)
+    }
+
+    /// Changes that may break existing tool calls
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &SpecChange> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    /// Namespace paths of operations whose tools disappear
+    pub fn removed_tools(&self) -> Vec<&str> {
+        self.changes
+            .iter()
+            .filter_map(|change| match change {
+                SpecChange::OperationRemoved { operation } => Some(operation.as_str()),
+                SpecChange::OperationRenamed { from, .. } => Some(from.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Compare two versions of the same operation
+fn compare_operation(
+    name: &str,
+    old: &ApiOperation,
+    new: &ApiOperation,
+    changes: &mut Vec<SpecChange>,
+) {
+    let operation = name.to_string();
+
+    if old.method != new.method || old.path != new.path {
+        changes.push(SpecChange::EndpointChanged {
+            operation: operation.clone(),
+            from: format!("{} {}", old.method, old.path),
+            to: format!("{} {}", new.method, new.path),
+        });
+    }
+
+    let old_params: BTreeMap<&str, bool> = old
+        .parameters
+        .iter()
+        .map(|p| (p.name.as_str(), p.required))
+        .collect();
+    let new_params: BTreeMap<&str, bool> = new
+        .parameters
+        .iter()
+        .map(|p| (p.name.as_str(), p.required))
+        .collect();
+    for (parameter, required) in &new_params {
+        let parameter = parameter.to_string();
+        let operation = operation.clone();
+        match old_params.get(parameter.as_str()) {
+            None => changes.push(SpecChange::ParameterAdded {
+                operation,
+                parameter,
+                required: *required,
+            }),
+            Some(false) if *required => changes.push(SpecChange::ParameterRequired {
+                operation,
+                parameter,
+            }),
+            Some(true) if !required => changes.push(SpecChange::ParameterOptional {
+                operation,
+                parameter,
+            }),
+            _ => {}
+        }
+    }
+    for parameter in old_params.keys().filter(|p| !new_params.contains_key(*p)) {
+        changes.push(SpecChange::ParameterRemoved {
+            operation: operation.clone(),
+            parameter: parameter.to_string(),
+        });
+    }
+
+    match (&old.request_body, &new.request_body) {
+        (None, Some(body)) => changes.push(SpecChange::BodyAdded {
+            operation: operation.clone(),
+            required: body.required,
+        }),
+        (Some(_), None) => changes.push(SpecChange::BodyRemoved {
+            operation: operation.clone(),
+        }),
+        (Some(old_body), Some(new_body)) => {
+            if new_body.required && !old_body.required {
+                changes.push(SpecChange::BodyRequired {
+                    operation: operation.clone(),
+                });
+            }
+            let old_required = required_fields(old_body.schema.as_ref());
+            for field in required_fields(new_body.schema.as_ref()).difference(&old_required) {
+                changes.push(SpecChange::BodyFieldRequired {
+                    operation: operation.clone(),
+                    field: field.clone(),
+                });
+            }
+        }
+        (None, None) => {}
+    }
+
+    if old.public != new.public || security_schemes(old) != security_schemes(new) {
+        changes.push(SpecChange::SecurityChanged { operation });
+    }
+}
+
+/// Top-level required properties of a JSON schema
+fn required_fields(schema: Option<&serde_json::Value>) -> BTreeSet<String> {
+    schema
+        .and_then(|s| s.get("required"))
+        .and_then(|r| r.as_array())
+        .map(|fields| {
+            fields
+                .iter()
+                .filter_map(|f| f.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Security scheme names and scopes an operation requires
+fn security_schemes(operation: &ApiOperation) -> BTreeSet<(String, BTreeSet<String>)> {
+    operation
+        .security
+        .iter()
+        .map(|req| {
+            (
+                req.scheme_name.clone(),
+                req.scopes.iter().cloned().collect(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openapi_parser::OpenApiParser;
+
+    const V1: &str = r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: OK
+    post:
+      operationId: createUser
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [name]
+      responses:
+        '201':
+          description: Created
+  /users/{id}:
+    delete:
+      operationId: deleteUser
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        '204':
+          description: Deleted
+  /status:
+    get:
+      operationId: getStatus
+      responses:
+        '200':
+          description: OK
+"#;
+
+    const V2: &str = r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "2.0.0"
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      parameters:
+        - name: limit
+          in: query
+          required: true
+          schema:
+            type: integer
+        - name: cursor
+          in: query
+          schema:
+            type: string
+      responses:
+        '200':
+          description: OK
+    post:
+      operationId: createUser
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [name, email]
+      responses:
+        '201':
+          description: Created
+  /status:
+    get:
+      operationId: getHealth
+      responses:
+        '200':
+          description: OK
+  /teams:
+    get:
+      operationId: listTeams
+      responses:
+        '200':
+          description: OK
+"#;
+
+    #[test]
+    fn test_compute_diff() {
+        let v1 = OpenApiParser::parse(V1).unwrap();
+        let v2 = OpenApiParser::parse(V2).unwrap();
+
+        let diff = SpecDiff::compute(&v1.operations, None, &v2.operations, None);
+        let op = |name: &str| name.to_string();
+        assert_eq!(
+            diff.changes,
+            vec![
+                SpecChange::BodyFieldRequired {
+                    operation: op("create.user"),
+                    field: "email".to_string()
+                },
+                SpecChange::OperationRemoved {
+                    operation: op("delete.user")
+                },
+                SpecChange::OperationRenamed {
+                    from: op("get.status"),
+                    to: op("get.health")
+                },
+                SpecChange::ParameterAdded {
+                    operation: op("list.users"),
+                    parameter: "cursor".to_string(),
+                    required: false
+                },
+                SpecChange::ParameterRequired {
+                    operation: op("list.users"),
+                    parameter: "limit".to_string()
+                },
+                SpecChange::OperationAdded {
+                    operation: op("list.teams")
+                },
+            ]
+        );
+        assert!(diff.is_breaking());
+        assert_eq!(diff.breaking_changes().count(), 4);
+        assert_eq!(diff.removed_tools(), vec!["delete.user", "get.status"]);
+
+        let same = SpecDiff::compute(&v1.operations, None, &v1.operations, None);
+        assert!(same.is_empty());
+
+        // Only additions are not breaking
+        let additive = SpecDiff::compute(&[], None, &v1.operations, None);
+        assert!(!additive.is_empty());
+        assert!(!additive.is_breaking());
+    }
+}
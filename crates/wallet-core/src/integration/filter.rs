@@ -0,0 +1,217 @@
+//! Per-integration filters deciding which operations are exposed as tools
+//!
+//! An operation is exposed when it matches at least one include rule (or
+//! there are none), matches no exclude rule and, with the read-only preset,
+//! does not modify anything. Filtered-out operations are hidden from
+//! listings and tools and cannot be called.
+
+use openapi_parser::{ApiOperation, HttpMethod};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+/// Most compiled path globs kept in the cache
+const MAX_CACHED_GLOBS: usize = 1024;
+
+/// Compiled regex of each path glob seen, so filters are not recompiled per call
+static GLOB_REGEXES: LazyLock<RwLock<HashMap<String, Regex>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Rule selecting operations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperationMatcher {
+    /// Operations with this tag (case-insensitive)
+    Tag { tag: String },
+    /// Operations using this HTTP method
+    Method { method: HttpMethod },
+    /// Operations whose URL path matches a glob
+    ///
+    /// `*` matches within one path segment, `**` across segments
+    /// (e.g., "/repos/*/issues/**").
+    Path { glob: String },
+    /// The operation with this operationId (or namespace ID)
+    OperationId { id: String },
+}
+
+impl OperationMatcher {
+    /// Whether the rule selects an operation
+    pub fn matches(&self, operation: &ApiOperation) -> bool {
+        match self {
+            Self::Tag { tag } => operation.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Self::Method { method } => operation.method == *method,
+            Self::Path { glob } => glob_regex(glob).is_match(&operation.path),
+            Self::OperationId { id } => {
+                operation.operation_id == *id || operation.normalized_id == *id
+            }
+        }
+    }
+}
+
+/// Which operations of an integration are exposed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationFilter {
+    /// Only operations matching one of these rules are exposed (all if empty)
+    #[serde(default)]
+    pub include: Vec<OperationMatcher>,
+
+    /// Operations matching any of these rules are hidden (wins over `include`)
+    #[serde(default)]
+    pub exclude: Vec<OperationMatcher>,
+
+    /// Read-only preset: only GET, HEAD and OPTIONS operations are exposed
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl OperationFilter {
+    /// The read-only preset
+    pub fn read_only() -> Self {
+        Self {
+            read_only: true,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the filter exposes every operation
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && !self.read_only
+    }
+
+    /// Whether an operation is exposed
+    pub fn allows(&self, operation: &ApiOperation) -> bool {
+        if self.read_only
+            && !matches!(
+                operation.method,
+                HttpMethod::Get | HttpMethod::Head | HttpMethod::Options
+            )
+        {
+            return false;
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|m| m.matches(operation)) {
+            return false;
+        }
+        !self.exclude.iter().any(|m| m.matches(operation))
+    }
+}
+
+/// The compiled regex of a path glob, from the cache when seen before
+fn glob_regex(glob: &str) -> Regex {
+    if let Some(regex) = GLOB_REGEXES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(glob)
+    {
+        return regex.clone();
+    }
+
+    let regex = glob_to_regex(glob);
+    let mut cache = GLOB_REGEXES.write().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= MAX_CACHED_GLOBS {
+        cache.clear();
+    }
+    cache.insert(glob.to_string(), regex.clone());
+    regex
+}
+
+/// Translate a path glob into an anchored regex
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).expect("escaped glob is a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(method: HttpMethod, path: &str, id: &str, tags: &[&str]) -> ApiOperation {
+        ApiOperation {
+            operation_id: id.to_string(),
+            normalized_id: id.to_string(),
+            method,
+            path: path.to_string(),
+            summary: None,
+            description: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            deprecated: false,
+            parameters: Vec::new(),
+            request_body: None,
+            responses: Vec::new(),
+            security: Vec::new(),
+            public: false,
+        }
+    }
+
+    #[test]
+    fn test_filter_rules() {
+        let list_issues = operation(
+            HttpMethod::Get,
+            "/repos/{owner}/{repo}/issues",
+            "listIssues",
+            &["issues"],
+        );
+        let delete_repo = operation(
+            HttpMethod::Delete,
+            "/repos/{owner}/{repo}",
+            "deleteRepo",
+            &["repos"],
+        );
+        let create_gist = operation(HttpMethod::Post, "/gists", "createGist", &["Gists"]);
+
+        assert!(OperationFilter::default().allows(&delete_repo));
+
+        let read_only = OperationFilter::read_only();
+        assert!(read_only.allows(&list_issues));
+        assert!(!read_only.allows(&delete_repo));
+
+        let filter = OperationFilter {
+            include: vec![
+                OperationMatcher::Path {
+                    glob: "/repos/**".to_string(),
+                },
+                OperationMatcher::Tag {
+                    tag: "gists".to_string(),
+                },
+            ],
+            exclude: vec![OperationMatcher::Method {
+                method: HttpMethod::Delete,
+            }],
+            read_only: false,
+        };
+        assert!(filter.allows(&list_issues));
+        assert!(!filter.allows(&delete_repo));
+        assert!(filter.allows(&create_gist));
+
+        let filter = OperationFilter {
+            exclude: vec![
+                OperationMatcher::OperationId {
+                    id: "createGist".to_string(),
+                },
+                OperationMatcher::Path {
+                    glob: "/repos/*".to_string(),
+                },
+            ],
+            ..OperationFilter::default()
+        };
+        assert!(filter.allows(&list_issues));
+        assert!(!filter.allows(&create_gist));
+        assert!(!filter.allows(&operation(HttpMethod::Get, "/repos/x", "getRepo", &[])));
+
+        // Globs are compiled once
+        assert!(GLOB_REGEXES.read().unwrap().contains_key("/repos/*"));
+    }
+}
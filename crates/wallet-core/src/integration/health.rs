@@ -0,0 +1,166 @@
+//! Integration health derived from the outcomes of real requests
+//!
+//! Every request made for an integration reports how it went. A rejected
+//! credential or an unreachable server marks the integration as errored
+//! right away; server errors only after several in a row. Rate limited
+//! requests change nothing. Any healthy response makes it active again.
+//! Disabled integrations are left alone.
+
+use super::types::{Integration, IntegrationStatus};
+
+/// Consecutive server errors after which an integration is marked as errored
+pub const SERVER_ERROR_THRESHOLD: u32 = 3;
+
+/// Outcome of a request to an integration, as far as its health is concerned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthOutcome {
+    /// The service answered and did not reject the credential
+    Healthy,
+    /// The credential was rejected (401 or 403)
+    AuthFailed(u16),
+    /// The server failed (5xx)
+    ServerError(u16),
+    /// The request hit a rate limit (429, or 403 with rate limit headers)
+    RateLimited(u16),
+    /// DNS resolution, connecting or the request timed out
+    Unreachable(String),
+}
+
+impl HealthOutcome {
+    /// Classify the status of an HTTP response
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 | 403 => Self::AuthFailed(status),
+            429 => Self::RateLimited(status),
+            500..=599 => Self::ServerError(status),
+            _ => Self::Healthy,
+        }
+    }
+
+    /// Classify an HTTP response
+    ///
+    /// Some services (GitHub among them) answer 403 when a rate limit is
+    /// hit; such a 403 does not count as a rejected credential.
+    pub fn from_response(status: u16, headers: &reqwest::header::HeaderMap) -> Self {
+        let rate_limited = headers.contains_key(reqwest::header::RETRY_AFTER)
+            || headers
+                .get("x-ratelimit-remaining")
+                .is_some_and(|remaining| remaining.as_bytes() == b"0");
+        match Self::from_status(status) {
+            Self::AuthFailed(403) if rate_limited => Self::RateLimited(403),
+            outcome => outcome,
+        }
+    }
+}
+
+impl Integration {
+    /// Update the status from a request outcome
+    ///
+    /// Returns whether anything changed (and needs to be saved).
+    pub fn record_health(&mut self, outcome: &HealthOutcome) -> bool {
+        if self.status == IntegrationStatus::Disabled {
+            return false;
+        }
+
+        let (status, error, server_errors) = match outcome {
+            HealthOutcome::Healthy => {
+                // A failing background sync keeps its message
+                let error = self.error.clone().filter(|_| self.has_sync_error());
+                (IntegrationStatus::Active, error, 0)
+            }
+            HealthOutcome::AuthFailed(status) => (
+                IntegrationStatus::Error,
+                Some(format!("Credential rejected ({})", status)),
+                0,
+            ),
+            HealthOutcome::ServerError(status) => {
+                let count = self.consecutive_server_errors + 1;
+                if count >= SERVER_ERROR_THRESHOLD {
+                    (
+                        IntegrationStatus::Error,
+                        Some(format!(
+                            "Server returned {} for {} requests in a row",
+                            status, count
+                        )),
+                        count,
+                    )
+                } else {
+                    (self.status, self.error.clone(), count)
+                }
+            }
+            HealthOutcome::RateLimited(_) => (
+                self.status,
+                self.error.clone(),
+                self.consecutive_server_errors,
+            ),
+            HealthOutcome::Unreachable(reason) => (
+                IntegrationStatus::Error,
+                Some(format!("Unreachable: {}", reason)),
+                0,
+            ),
+        };
+
+        let changed = status != self.status
+            || error != self.error
+            || server_errors != self.consecutive_server_errors;
+        self.status = status;
+        self.error = error;
+        self.consecutive_server_errors = server_errors;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions() {
+        let spec = openapi_parser::OpenApiParser::parse(
+            r#"{"openapi": "3.0.0", "info": {"title": "Test", "version": "1"}, "paths": {}}"#,
+        )
+        .unwrap();
+        let mut integration = Integration::from_spec("test".to_string(), &spec);
+        assert_eq!(integration.status, IntegrationStatus::Pending);
+
+        assert!(integration.record_health(&HealthOutcome::from_status(200)));
+        assert_eq!(integration.status, IntegrationStatus::Active);
+        assert!(!integration.record_health(&HealthOutcome::from_status(404)));
+
+        // Server errors only count after several in a row
+        for _ in 1..SERVER_ERROR_THRESHOLD {
+            integration.record_health(&HealthOutcome::from_status(503));
+            assert_eq!(integration.status, IntegrationStatus::Active);
+        }
+        integration.record_health(&HealthOutcome::from_status(503));
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        assert!(integration.error.as_ref().unwrap().contains("503"));
+
+        integration.record_health(&HealthOutcome::Healthy);
+        assert_eq!(integration.status, IntegrationStatus::Active);
+        assert_eq!(integration.error, None);
+        assert_eq!(integration.consecutive_server_errors, 0);
+
+        // A 403 for a rate limit is not a rejected credential
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        assert_eq!(
+            HealthOutcome::from_response(403, &headers),
+            HealthOutcome::RateLimited(403)
+        );
+        assert!(!integration.record_health(&HealthOutcome::from_response(403, &headers)));
+        assert!(!integration.record_health(&HealthOutcome::from_status(429)));
+        assert_eq!(integration.status, IntegrationStatus::Active);
+
+        integration.record_health(&HealthOutcome::from_response(403, &Default::default()));
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        integration.record_health(&HealthOutcome::from_status(401));
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        integration.record_health(&HealthOutcome::Unreachable("dns error".to_string()));
+        assert!(integration.error.as_ref().unwrap().contains("dns error"));
+
+        integration.status = IntegrationStatus::Disabled;
+        assert!(!integration.record_health(&HealthOutcome::Healthy));
+        assert_eq!(integration.status, IntegrationStatus::Disabled);
+    }
+}
@@ -1,7 +1,17 @@
 //! Integration management for OpenAPI-based services
 
+mod diff;
+mod filter;
+mod health;
 mod registry;
+mod templates;
+mod tools;
 mod types;
 
+pub use diff::{SpecChange, SpecDiff};
+pub use filter::{OperationFilter, OperationMatcher};
+pub use health::{HealthOutcome, SERVER_ERROR_THRESHOLD};
 pub use registry::IntegrationRegistry;
+pub use templates::{IntegrationTemplate, TemplateCatalog, TemplateSpec};
+pub use tools::{tool_name, validate_key};
 pub use types::*;
@@ -1,25 +1,114 @@
 //! Integration registry for managing multiple integrations
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use super::types::{Integration, IntegrationStatus, StoredIntegration};
+use super::diff::SpecDiff;
+use super::filter::OperationFilter;
+use super::health::HealthOutcome;
+use super::templates::{TemplateCatalog, TemplateSpec};
+use super::tools::validate_key;
+use super::types::{
+    spec_hash, CredentialBinding, Integration, IntegrationStatus, SigningConfig, SpecHistoryEntry,
+    SpecRevision, StoredIntegration, SyncReport, SYNC_ERROR_PREFIX,
+};
 use crate::error::{Result, WalletError};
 use crate::storage::SecureStorage;
-use openapi_parser::{ApiOperation, OpenApiParser};
+use openapi_parser::{
+    ApiOperation, AuthScheme, FetchedSpec, NamespaceTree, OpenApiParser, Overlay, ParsedSpec,
+    SecurityScheme,
+};
 
 /// Storage key prefix for integrations
 const INTEGRATION_PREFIX: &str = "integration:";
 
+/// Storage key prefix for replaced spec revisions (`{prefix}{key}:{revision}`)
+const REVISION_PREFIX: &str = "integration_revision:";
+
+/// Number of replaced spec revisions kept per integration
+const MAX_REVISIONS: u32 = 10;
+
+/// Shortest allowed periodic credential check interval (seconds)
+const MIN_HEALTH_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Longest allowed periodic credential check interval (seconds)
+const MAX_HEALTH_CHECK_INTERVAL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Shortest allowed background sync interval (seconds)
+const MIN_SYNC_INTERVAL_SECS: u64 = 60;
+
+/// Longest allowed background sync interval (seconds)
+const MAX_SYNC_INTERVAL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Longest delay before retrying a failing background sync (seconds)
+const MAX_SYNC_BACKOFF_SECS: u64 = 24 * 60 * 60;
+
+/// Spec fetched (or read from disk) for a sync
+struct RemoteSpec {
+    spec: ParsedSpec,
+    fetched: FetchedSpec,
+    diff: SpecDiff,
+}
+
+/// What the file watcher last saw of an integration's spec files
+struct SeenFiles {
+    /// Hash of the bundled content (or of the error loading it)
+    hash: String,
+    /// Modification time and size of each file (`None` when it could not be read)
+    stamps: Vec<(PathBuf, Option<(std::time::SystemTime, u64)>)>,
+}
+
+impl SeenFiles {
+    /// Modification time and size of a file
+    fn file_stamp(file: &Path) -> Option<(std::time::SystemTime, u64)> {
+        std::fs::metadata(file)
+            .and_then(|meta| Ok((meta.modified()?, meta.len())))
+            .ok()
+    }
+
+    /// Stamp files read at `read_at`
+    ///
+    /// Files modified since are left unstamped, so they are read again.
+    fn stamp(
+        files: Vec<PathBuf>,
+        read_at: std::time::SystemTime,
+    ) -> Vec<(PathBuf, Option<(std::time::SystemTime, u64)>)> {
+        files
+            .into_iter()
+            .map(|file| {
+                let stamp = Self::file_stamp(&file).filter(|(modified, _)| *modified < read_at);
+                (file, stamp)
+            })
+            .collect()
+    }
+
+    /// Whether none of the files changed since they were seen
+    fn unchanged(&self) -> bool {
+        self.stamps
+            .iter()
+            .all(|(file, stamp)| stamp.is_some() && Self::file_stamp(file) == *stamp)
+    }
+}
+
 /// Registry for managing integrations
+///
+/// Clones share the same cache and storage.
+#[derive(Clone)]
 pub struct IntegrationRegistry {
     /// In-memory cache of integrations
     integrations: Arc<RwLock<HashMap<String, StoredIntegration>>>,
     /// Storage backend
     storage: Arc<dyn SecureStorage>,
+    /// User catalog of integration templates (merged over the built-in one)
+    catalog_file: Option<PathBuf>,
+    /// Spec files last seen by the file watcher, by integration key
+    seen_files: Arc<RwLock<HashMap<String, SeenFiles>>>,
+    /// Integration key and operation index of each tool name
+    tools: Arc<RwLock<HashMap<String, (String, usize)>>>,
 }
 
 impl IntegrationRegistry {
@@ -28,9 +117,18 @@ impl IntegrationRegistry {
         Self {
             integrations: Arc::new(RwLock::new(HashMap::new())),
             storage,
+            catalog_file: None,
+            seen_files: Arc::new(RwLock::new(HashMap::new())),
+            tools: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Read user integration templates from a catalog file at the given path
+    pub fn with_catalog_file(mut self, path: PathBuf) -> Self {
+        self.catalog_file = Some(path);
+        self
+    }
+
     /// Load all integrations from storage
     pub async fn load(&self) -> Result<()> {
         let keys = self.storage.list_keys(INTEGRATION_PREFIX).await?;
@@ -40,7 +138,11 @@ impl IntegrationRegistry {
         for key in keys {
             match self.storage.retrieve(&key).await? {
                 Some(data) => {
-                    let stored: StoredIntegration = serde_json::from_slice(&data)?;
+                    let mut stored: StoredIntegration = serde_json::from_slice(&data)?;
+                    // Integrations saved before tool names were stored
+                    if stored.tool_names.len() != stored.operations.len() {
+                        stored.assign_tool_names(None);
+                    }
                     let integration_key = key
                         .strip_prefix(INTEGRATION_PREFIX)
                         .unwrap_or(&key)
@@ -54,6 +156,39 @@ impl IntegrationRegistry {
         }
 
         info!("Loaded {} integrations", integrations.len());
+        drop(integrations);
+
+        self.index_tools().await;
+        Ok(())
+    }
+
+    /// Reload one integration from storage if another process saved a newer copy
+    ///
+    /// Keeps the cached copy when it is at least as recent, so changes not
+    /// yet saved by this process are not lost.
+    async fn reload(&self, key: &str) -> Result<()> {
+        let storage_key = format!("{}{}", INTEGRATION_PREFIX, key);
+        let Some(data) = self.storage.retrieve(&storage_key).await? else {
+            return Err(WalletError::IntegrationNotFound(key.to_string()));
+        };
+        let mut saved: StoredIntegration = serde_json::from_slice(&data)?;
+
+        let mut integrations = self.integrations.write().await;
+        if integrations
+            .get(key)
+            .is_some_and(|cached| cached.integration.updated_at >= saved.integration.updated_at)
+        {
+            return Ok(());
+        }
+
+        if saved.tool_names.len() != saved.operations.len() {
+            saved.assign_tool_names(None);
+        }
+        integrations.insert(key.to_string(), saved);
+        drop(integrations);
+
+        self.index_tools().await;
+        debug!("Reloaded integration {} from storage", key);
         Ok(())
     }
 
@@ -61,21 +196,133 @@ impl IntegrationRegistry {
     pub async fn add_from_url(&self, key: &str, spec_url: &str) -> Result<Integration> {
         info!("Adding integration from URL: {} -> {}", key, spec_url);
 
-        let spec = OpenApiParser::fetch_and_parse(spec_url)
+        let stored = Self::fetch_new(key, spec_url).await?;
+        self.insert(key, stored, true).await
+    }
+
+    /// Fetch and parse a spec for a new integration
+    async fn fetch_new(key: &str, spec_url: &str) -> Result<StoredIntegration> {
+        validate_key(key)?;
+        let fetched = OpenApiParser::fetch_if_modified(spec_url, None, None)
             .await
+            .map_err(|e| WalletError::InvalidSpec(e.to_string()))?
+            .ok_or_else(|| WalletError::InvalidSpec(format!("No spec content at {}", spec_url)))?;
+        let spec = OpenApiParser::parse_fetched(spec_url, &fetched.content)
             .map_err(|e| WalletError::InvalidSpec(e.to_string()))?;
 
-        let mut stored = StoredIntegration::from_spec(key.to_string(), spec, None);
+        let mut stored = StoredIntegration::from_spec(key.to_string(), spec, Some(fetched.content));
         stored.integration.spec_url = Some(spec_url.to_string());
+        stored.integration.spec_etag = fetched.etag;
+        stored.integration.spec_last_modified = fetched.last_modified;
+        stored.refresh_server_url();
 
-        self.save_integration(&stored).await?;
+        Ok(stored)
+    }
 
-        let integration = stored.integration.clone();
+    /// Add an integration from a local spec file
+    ///
+    /// Relative `$ref`s to sibling files are resolved, and the integration is
+    /// reloaded when the file or a file it references changes (see
+    /// [`Self::reload_changed_files`]).
+    pub async fn add_from_path(&self, key: &str, path: &Path) -> Result<Integration> {
+        info!(
+            "Adding integration from file: {} -> {}",
+            key,
+            path.display()
+        );
+
+        let local =
+            OpenApiParser::load_file(path).map_err(|e| WalletError::InvalidSpec(e.to_string()))?;
+        let spec = Self::parse_spec(&local.content, None, None)?;
+
+        let mut stored = StoredIntegration::from_spec(key.to_string(), spec, Some(local.content));
+        stored.integration.spec_path = Some(local.path.display().to_string());
+
+        self.seen_files.write().await.remove(key);
+        self.insert(key, stored, true).await
+    }
 
-        let mut integrations = self.integrations.write().await;
-        integrations.insert(key.to_string(), stored);
+    /// Reload integrations whose spec files changed since they were last seen
+    ///
+    /// Files are only read again when their modification time or size
+    /// changed. Changed content is applied like a sync; content that fails
+    /// to load is reported once and tried again when the files change again.
+    pub async fn reload_changed_files(&self) -> Vec<(String, Result<SyncReport>)> {
+        let watched: Vec<(String, String, Option<String>)> = {
+            let integrations = self.integrations.read().await;
+            integrations
+                .iter()
+                .filter_map(|(key, stored)| {
+                    let path = stored.integration.spec_path.clone()?;
+                    Some((key.clone(), path, stored.integration.spec_hash.clone()))
+                })
+                .collect()
+        };
 
-        Ok(integration)
+        let mut results = Vec::new();
+        for (key, path, current_hash) in watched {
+            if self
+                .seen_files
+                .read()
+                .await
+                .get(&key)
+                .is_some_and(SeenFiles::unchanged)
+            {
+                continue;
+            }
+
+            let read_at = std::time::SystemTime::now();
+            let (hash, files) = match OpenApiParser::load_file(Path::new(&path)) {
+                Ok(local) => (spec_hash(&local.content), local.files),
+                Err(e) => {
+                    // Keep watching the files seen before, one may be missing
+                    let mut files = vec![PathBuf::from(&path)];
+                    if let Some(seen) = self.seen_files.read().await.get(&key) {
+                        for (file, _) in &seen.stamps {
+                            if !files.contains(file) {
+                                files.push(file.clone());
+                            }
+                        }
+                    }
+                    (spec_hash(&e.to_string()), files)
+                }
+            };
+            let stamps = SeenFiles::stamp(files, read_at);
+
+            let mut seen = self.seen_files.write().await;
+            let last_seen = seen.get(&key).map(|s| s.hash.clone()).or(current_hash);
+            seen.insert(
+                key.clone(),
+                SeenFiles {
+                    hash: hash.clone(),
+                    stamps,
+                },
+            );
+            drop(seen);
+            if last_seen.as_ref() == Some(&hash) {
+                continue;
+            }
+
+            info!("Spec file of integration {} changed: {}", key, path);
+            let result = self.sync(&key, false).await;
+            results.push((key, result));
+        }
+        results
+    }
+
+    /// Parse spec content, applying an overlay first if there is one
+    fn parse_spec(
+        content: &str,
+        spec_url: Option<&str>,
+        overlay: Option<&str>,
+    ) -> Result<ParsedSpec> {
+        let parsed = match (overlay, spec_url) {
+            (Some(overlay), _) => Overlay::parse(overlay)
+                .and_then(|overlay| OpenApiParser::parse_with_overlay(content, &overlay)),
+            (None, Some(url)) => OpenApiParser::parse_fetched(url, content),
+            (None, None) => OpenApiParser::parse(content),
+        };
+        parsed.map_err(|e| WalletError::InvalidSpec(e.to_string()))
     }
 
     /// Add an integration from spec content
@@ -87,12 +334,95 @@ impl IntegrationRegistry {
 
         let stored = StoredIntegration::from_spec(key.to_string(), spec, Some(content.to_string()));
 
-        self.save_integration(&stored).await?;
+        self.insert(key, stored, true).await
+    }
 
-        let integration = stored.integration.clone();
+    /// List the available integration templates (built-in and user)
+    pub fn templates(&self) -> Result<TemplateCatalog> {
+        let mut catalog = TemplateCatalog::builtin();
+        if let Some(path) = self.catalog_file.as_deref().filter(|path| path.exists()) {
+            catalog.merge(TemplateCatalog::load(path)?);
+        }
+        Ok(catalog)
+    }
+
+    /// Add an integration from a template of the catalog
+    ///
+    /// The integration is created under the template's key with its
+    /// provider, authentication, operation filter, server and probe
+    /// settings. An existing integration with that key is not replaced.
+    pub async fn add_from_template(&self, name: &str) -> Result<Integration> {
+        let catalog = self.templates()?;
+        let template = catalog.get(name).ok_or_else(|| {
+            WalletError::IntegrationNotFound(format!("No template named {}", name))
+        })?;
+        let key = template.key();
+        if self.integrations.read().await.contains_key(key) {
+            return Err(WalletError::InvalidIntegrationKey(format!(
+                "{} is already in use",
+                key
+            )));
+        }
+        info!("Adding integration from template: {} -> {}", key, name);
+
+        let mut stored = match &template.spec {
+            TemplateSpec::Url { url } => Self::fetch_new(key, url).await?,
+            TemplateSpec::File { path } => {
+                let content = template.read_spec_file(path)?;
+                let spec = OpenApiParser::parse(&content)
+                    .map_err(|e| WalletError::InvalidSpec(e.to_string()))?;
+                StoredIntegration::from_spec(key.to_string(), spec, Some(content))
+            }
+        };
+        template.apply(&mut stored)?;
+
+        // Another integration may have taken the key during the fetch
+        self.insert(key, stored, false).await
+    }
+
+    /// Store a newly added integration
+    ///
+    /// An integration it replaces is kept in the history, and the new one
+    /// continues its revision numbers and tool names. Without `replace`, an
+    /// existing integration with the key is an error.
+    async fn insert(
+        &self,
+        key: &str,
+        mut stored: StoredIntegration,
+        replace: bool,
+    ) -> Result<Integration> {
+        validate_key(key)?;
 
         let mut integrations = self.integrations.write().await;
-        integrations.insert(key.to_string(), stored);
+        if !replace && integrations.contains_key(key) {
+            return Err(WalletError::InvalidIntegrationKey(format!(
+                "{} is already in use",
+                key
+            )));
+        }
+
+        let previous = integrations.get(key).map(|replaced| {
+            stored.assign_tool_names(Some(replaced));
+            SpecRevision::capture(replaced)
+        });
+        if let Some(previous) = &previous {
+            stored.integration.revision = previous.revision + 1;
+        }
+
+        let integration = stored.integration.clone();
+        integrations.insert(key.to_string(), stored.clone());
+        drop(integrations);
+
+        // The cache holds the new integration even if saving fails
+        let saved = async {
+            if let Some(previous) = &previous {
+                self.save_revision(key, previous).await?;
+            }
+            self.save_integration(&stored).await
+        }
+        .await;
+        self.index_tools().await;
+        saved?;
 
         Ok(integration)
     }
@@ -103,13 +433,49 @@ impl IntegrationRegistry {
 
         let storage_key = format!("{}{}", INTEGRATION_PREFIX, key);
         self.storage.delete(&storage_key).await?;
+        for (_, revision_key) in self.revision_keys(key).await? {
+            self.storage.delete(&revision_key).await?;
+        }
 
         let mut integrations = self.integrations.write().await;
         integrations.remove(key);
+        drop(integrations);
 
+        self.index_tools().await;
         Ok(())
     }
 
+    /// Find the integration and operation index behind a tool name
+    pub async fn resolve_tool(&self, tool_name: &str) -> Option<(String, usize)> {
+        self.tools.read().await.get(tool_name).cloned()
+    }
+
+    /// Rebuild the tool name index from the integrations' tool names
+    ///
+    /// Names are unique within an integration, and valid keys keep them
+    /// apart across integrations. Should integrations saved with older keys
+    /// still produce the same name, the first key in order keeps it.
+    async fn index_tools(&self) {
+        let integrations = self.integrations.read().await;
+        let mut keys: Vec<&String> = integrations.keys().collect();
+        keys.sort();
+
+        let mut tools: HashMap<String, (String, usize)> = HashMap::new();
+        for key in keys {
+            for (index, name) in integrations[key].tool_names.iter().enumerate() {
+                if let Some((other, _)) = tools.get(name) {
+                    warn!("Tool {} of {} is already provided by {}", name, key, other);
+                    continue;
+                }
+                tools.insert(name.clone(), (key.clone(), index));
+            }
+        }
+        drop(integrations);
+
+        debug!("Indexed {} tools", tools.len());
+        *self.tools.write().await = tools;
+    }
+
     /// Get an integration by key
     pub async fn get(&self, key: &str) -> Option<Integration> {
         let integrations = self.integrations.read().await;
@@ -154,7 +520,9 @@ impl IntegrationRegistry {
 
         if let Some(stored) = integrations.get_mut(key) {
             stored.integration.credential_id = Some(credential_id);
-            stored.integration.status = IntegrationStatus::Active;
+            if stored.integration.status != IntegrationStatus::Disabled {
+                stored.integration.status = IntegrationStatus::Active;
+            }
             stored.integration.updated_at = chrono::Utc::now();
 
             // Persist
@@ -168,6 +536,403 @@ impl IntegrationRegistry {
         Ok(())
     }
 
+    /// Bind a labeled credential, replacing any binding with the same label
+    ///
+    /// The first binding, or one bound with `make_default`, becomes the
+    /// integration's default credential.
+    pub async fn bind_credential(
+        &self,
+        key: &str,
+        binding: CredentialBinding,
+        make_default: bool,
+    ) -> Result<()> {
+        if binding.label.is_empty() {
+            return Err(WalletError::InvalidCredential(
+                "Credential labels must not be empty".to_string(),
+            ));
+        }
+
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+        let integration = &mut stored.integration;
+
+        let credential_id = binding.credential_id;
+        let label = binding.label.clone();
+        integration
+            .credential_bindings
+            .retain(|b| b.label != binding.label);
+        integration.credential_bindings.push(binding);
+
+        if make_default || integration.credential_id.is_none() {
+            integration.credential_id = Some(credential_id);
+        }
+        if integration.status != IntegrationStatus::Disabled {
+            integration.status = IntegrationStatus::Active;
+        }
+        integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Bound credential {} as {} for integration {}",
+            credential_id, label, key
+        );
+        Ok(())
+    }
+
+    /// Remove a labeled credential binding
+    ///
+    /// Removing the default falls back to the first remaining binding.
+    pub async fn unbind_credential(&self, key: &str, label: &str) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+        let integration = &mut stored.integration;
+
+        let index = integration
+            .credential_bindings
+            .iter()
+            .position(|b| b.label == label)
+            .ok_or_else(|| {
+                WalletError::CredentialNotFound(format!(
+                    "No credential labeled {} for {}",
+                    label, key
+                ))
+            })?;
+        let removed = integration.credential_bindings.remove(index);
+
+        if integration.credential_id == Some(removed.credential_id) {
+            integration.credential_id = integration
+                .credential_bindings
+                .first()
+                .map(|b| b.credential_id);
+            if integration.credential_id.is_none()
+                && integration.status != IntegrationStatus::Disabled
+            {
+                integration.status = IntegrationStatus::Pending;
+            }
+        }
+        integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!("Unbound credential {} from integration {}", label, key);
+        Ok(())
+    }
+
+    /// Set the environment used to pick credentials (`None` uses the default)
+    pub async fn set_environment(&self, key: &str, environment: Option<&str>) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        stored.integration.environment = environment.map(String::from);
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!("Set environment {:?} for integration {}", environment, key);
+        Ok(())
+    }
+
+    /// Set the operation used to check credentials (`None` picks one automatically)
+    pub async fn set_probe_operation(&self, key: &str, operation: Option<&str>) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        if let Some(operation) = operation {
+            if stored.lookup_operation(operation).is_none() {
+                return Err(WalletError::OperationNotFound(operation.to_string()));
+            }
+        }
+
+        stored.integration.probe_operation = operation.map(String::from);
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Set probe operation {:?} for integration {}",
+            operation, key
+        );
+        Ok(())
+    }
+
+    /// Record that a credential check ran and schedule the next one
+    ///
+    /// The probe's response updates the status like any request (see
+    /// `record_health`); `error` is for a probe that could not be sent, and
+    /// marks the integration as errored with that message.
+    pub async fn record_validation(&self, key: &str, error: Option<String>) -> Result<Integration> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        let now = chrono::Utc::now();
+        if let Some(error) = error {
+            if stored.integration.status != IntegrationStatus::Disabled {
+                stored.integration.status = IntegrationStatus::Error;
+                stored.integration.error = Some(error);
+            }
+        }
+        stored.integration.last_validated_at = Some(now);
+        stored.integration.next_health_check_at = stored
+            .integration
+            .health_check_interval_secs
+            .map(|interval| schedule_after(now, interval));
+        stored.integration.updated_at = now;
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Recorded credential check for integration {}: {:?}",
+            key, stored_clone.integration.status
+        );
+        Ok(stored_clone.integration)
+    }
+
+    /// Update an integration's status from the outcome of a request
+    ///
+    /// Only saved when the status or error counters change.
+    pub async fn record_health(&self, key: &str, outcome: &HealthOutcome) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        let previous = stored.integration.status;
+        if !stored.integration.record_health(outcome) {
+            return Ok(());
+        }
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        if stored_clone.integration.status != previous {
+            info!(
+                "Integration {} is now {:?} after {:?}",
+                key, stored_clone.integration.status, outcome
+            );
+        }
+        Ok(())
+    }
+
+    /// Enable or disable an integration
+    ///
+    /// Disabled integrations offer no tools and are not probed. An enabled
+    /// integration is pending until its credential is used or checked.
+    pub async fn set_enabled(&self, key: &str, enabled: bool) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        if enabled != (stored.integration.status == IntegrationStatus::Disabled) {
+            return Ok(());
+        }
+        stored.integration.status = if enabled {
+            IntegrationStatus::Pending
+        } else {
+            IntegrationStatus::Disabled
+        };
+        stored.integration.error = None;
+        stored.integration.consecutive_server_errors = 0;
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        info!(
+            "{} integration {}",
+            if enabled { "Enabled" } else { "Disabled" },
+            key
+        );
+        Ok(())
+    }
+
+    /// Set how often an integration's credential is checked against its probe operation
+    ///
+    /// `None` turns periodic checks off. The first check is due right away.
+    pub async fn set_health_check_interval(
+        &self,
+        key: &str,
+        interval_secs: Option<u64>,
+    ) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        if let Some(interval) = interval_secs {
+            if stored.probe_operation().is_none() {
+                return Err(WalletError::OperationNotFound(format!(
+                    "No probe operation for integration {}",
+                    key
+                )));
+            }
+            if !(MIN_HEALTH_CHECK_INTERVAL_SECS..=MAX_HEALTH_CHECK_INTERVAL_SECS)
+                .contains(&interval)
+            {
+                return Err(WalletError::InvalidSpec(format!(
+                    "Health check interval must be between {} and {} seconds",
+                    MIN_HEALTH_CHECK_INTERVAL_SECS, MAX_HEALTH_CHECK_INTERVAL_SECS
+                )));
+            }
+        }
+
+        stored.integration.health_check_interval_secs = interval_secs;
+        stored.integration.next_health_check_at = None;
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Set health check interval {:?} for integration {}",
+            interval_secs, key
+        );
+        Ok(())
+    }
+
+    /// Keys of integrations whose periodic credential check is due
+    pub async fn health_checks_due(&self) -> Vec<String> {
+        let now = chrono::Utc::now();
+        let integrations = self.integrations.read().await;
+        let mut keys: Vec<String> = integrations
+            .iter()
+            .filter(|(_, stored)| stored.integration.health_check_due(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Sign requests to an integration (`None` disables signing)
+    pub async fn set_signing(&self, key: &str, signing: Option<SigningConfig>) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        stored.integration.signing = signing;
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!("Set request signing for integration {}", key);
+        Ok(())
+    }
+
+    /// Override the detected authentication scheme (`None` restores detection)
+    pub async fn set_auth_override(&self, key: &str, scheme: Option<AuthScheme>) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        stored.integration.auth_override = scheme;
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!("Set auth override for integration {}", key);
+        Ok(())
+    }
+
+    /// Map a multi-field credential field to an apiKey security scheme
+    ///
+    /// Passing `None` removes the mapping.
+    pub async fn set_credential_field(
+        &self,
+        key: &str,
+        field: &str,
+        scheme_name: Option<&str>,
+    ) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        match scheme_name {
+            Some(scheme_name) => {
+                if !matches!(
+                    stored.security_schemes.get(scheme_name),
+                    Some(SecurityScheme::ApiKey { .. })
+                ) {
+                    return Err(WalletError::InvalidSpec(format!(
+                        "{} has no apiKey security scheme named {}",
+                        key, scheme_name
+                    )));
+                }
+                stored
+                    .integration
+                    .credential_fields
+                    .insert(field.to_string(), scheme_name.to_string());
+            }
+            None => {
+                stored.integration.credential_fields.remove(field);
+            }
+        }
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Mapped credential field {} to {:?} for integration {}",
+            field, scheme_name, key
+        );
+        Ok(())
+    }
+
     /// Look up an operation by integration key and operation path
     pub async fn lookup_operation(&self, key: &str, path: &str) -> Option<ApiOperation> {
         let integrations = self.integrations.read().await;
@@ -177,12 +942,12 @@ impl IntegrationRegistry {
             .cloned()
     }
 
-    /// List all operations for an integration
+    /// List the operations an integration exposes (after its filter)
     pub async fn list_operations(&self, key: &str) -> Vec<ApiOperation> {
         let integrations = self.integrations.read().await;
         integrations
             .get(key)
-            .map(|s| s.operations.clone())
+            .map(|s| s.allowed_operations().cloned().collect())
             .unwrap_or_default()
     }
 
@@ -209,59 +974,690 @@ impl IntegrationRegistry {
         Ok(())
     }
 
+    /// Fetch an integration's remote spec and compare it with the stored one
+    pub async fn preview_sync(&self, key: &str) -> Result<SpecDiff> {
+        let remote = self.fetch_remote(key, false).await?;
+        Ok(remote.map(|remote| remote.diff).unwrap_or_default())
+    }
+
     /// Sync an integration (re-fetch and update spec)
-    pub async fn sync(&self, key: &str) -> Result<()> {
-        let spec_url = {
+    ///
+    /// The replaced spec is kept as a revision. If the integration requires
+    /// confirmation, a sync with breaking changes is only applied when
+    /// `confirm` is set; otherwise the report lists the changes unapplied.
+    /// A spec that was rolled back is not applied again until it changes.
+    /// The outcome updates the integration's sync status and schedule.
+    pub async fn sync(&self, key: &str, confirm: bool) -> Result<SyncReport> {
+        self.reload(key).await?;
+        self.sync_loaded(key, confirm).await
+    }
+
+    /// Sync an integration just reloaded from storage (see `sync`)
+    async fn sync_loaded(&self, key: &str, confirm: bool) -> Result<SyncReport> {
+        let result = self.sync_spec(key, confirm).await;
+        match &result {
+            Ok(_) => self.record_sync(key, None).await?,
+            Err(WalletError::IntegrationNotFound(_)) => {}
+            Err(e) => self.record_sync(key, Some(e.redacted())).await?,
+        }
+        result
+    }
+
+    /// Sync every integration whose background sync is due
+    pub async fn sync_due(&self) -> Vec<(String, Result<SyncReport>)> {
+        let now = chrono::Utc::now();
+        let mut keys: Vec<String> = {
             let integrations = self.integrations.read().await;
             integrations
-                .get(key)
-                .and_then(|s| s.integration.spec_url.clone())
+                .iter()
+                .filter(|(_, stored)| stored.integration.sync_due(now))
+                .map(|(key, _)| key.clone())
+                .collect()
         };
+        keys.sort();
 
-        let spec_url = spec_url
-            .ok_or_else(|| WalletError::IntegrationNotFound(format!("{} has no spec URL", key)))?;
+        let mut results = Vec::new();
+        for key in keys {
+            // Another process may have synced or changed it since
+            if let Err(e) = self.reload(&key).await {
+                warn!("Failed to reload integration {}: {}", key, e);
+                continue;
+            }
+            let due = self
+                .integrations
+                .read()
+                .await
+                .get(&key)
+                .is_some_and(|stored| stored.integration.sync_due(now));
+            if !due {
+                continue;
+            }
 
-        info!("Syncing integration: {} from {}", key, spec_url);
+            let result = self.sync_loaded(&key, false).await;
+            if let Err(e) = &result {
+                warn!("Background sync of {} failed: {}", key, e.redacted());
+            }
+            results.push((key, result));
+        }
+        results
+    }
 
-        let spec = OpenApiParser::fetch_and_parse(&spec_url)
-            .await
-            .map_err(|e| WalletError::InvalidSpec(e.to_string()))?;
+    /// Set how often an integration's remote spec is synced in the background
+    ///
+    /// `None` turns background syncs off. The first sync is due one interval
+    /// from now.
+    pub async fn set_sync_interval(&self, key: &str, interval_secs: Option<u64>) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
 
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        if let Some(interval) = interval_secs {
+            if stored.integration.spec_url.is_none() {
+                return Err(WalletError::InvalidSpec(format!(
+                    "{} has no spec URL to sync from",
+                    key
+                )));
+            }
+            if !(MIN_SYNC_INTERVAL_SECS..=MAX_SYNC_INTERVAL_SECS).contains(&interval) {
+                return Err(WalletError::InvalidSpec(format!(
+                    "Sync interval must be between {} and {} seconds",
+                    MIN_SYNC_INTERVAL_SECS, MAX_SYNC_INTERVAL_SECS
+                )));
+            }
+        }
+
+        let now = chrono::Utc::now();
+        stored.integration.sync_interval_secs = interval_secs;
+        stored.integration.next_sync_at =
+            interval_secs.map(|interval| schedule_after(now, interval));
+        stored.integration.updated_at = now;
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Set sync interval {:?} for integration {}",
+            interval_secs, key
+        );
+        Ok(())
+    }
+
+    /// Record the outcome of a sync and schedule the next background sync
+    ///
+    /// Failures are kept in `error` (unless it holds another error) and push
+    /// the next attempt back exponentially (capped at a day). Only errors
+    /// written by syncs are cleared by a successful one.
+    async fn record_sync(&self, key: &str, error: Option<String>) -> Result<()> {
         let mut integrations = self.integrations.write().await;
 
-        if let Some(stored) = integrations.get_mut(key) {
-            // Preserve credential_id and status
-            let credential_id = stored.integration.credential_id;
-            let status = stored.integration.status;
+        let Some(stored) = integrations.get_mut(key) else {
+            return Ok(());
+        };
 
-            // Update with new spec
-            let mut new_stored = StoredIntegration::from_spec(key.to_string(), spec, None);
-            new_stored.integration.spec_url = Some(spec_url);
-            new_stored.integration.credential_id = credential_id;
-            new_stored.integration.status = status;
-            new_stored.integration.id = stored.integration.id;
-            new_stored.integration.created_at = stored.integration.created_at;
+        let integration = &mut stored.integration;
+        match error {
+            None => {
+                if integration.has_sync_error() {
+                    integration.error = None;
+                }
+                integration.sync_failures = 0;
+            }
+            Some(error) => {
+                if integration.error.is_none() || integration.has_sync_error() {
+                    integration.error = Some(format!("{}{}", SYNC_ERROR_PREFIX, error));
+                }
+                integration.sync_failures += 1;
+            }
+        }
 
-            *stored = new_stored;
+        let now = chrono::Utc::now();
+        integration.next_sync_at = integration.sync_interval_secs.map(|interval| {
+            let backoff = 2u64.saturating_pow(integration.sync_failures);
+            let delay = interval
+                .saturating_mul(backoff)
+                .min(MAX_SYNC_BACKOFF_SECS.max(interval));
+            schedule_after(now, delay)
+        });
+        integration.updated_at = now;
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Recorded sync of integration {} ({} consecutive failures)",
+            key, stored_clone.integration.sync_failures
+        );
+        Ok(())
+    }
+
+    /// Fetch the remote spec and apply it (see `sync`)
+    async fn sync_spec(&self, key: &str, confirm: bool) -> Result<SyncReport> {
+        let Some(RemoteSpec {
+            spec,
+            fetched,
+            diff,
+        }) = self.fetch_remote(key, true).await?
+        else {
+            // Not modified since the last fetch
+            let mut integrations = self.integrations.write().await;
+
+            let stored = integrations
+                .get_mut(key)
+                .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+            stored.integration.last_synced_at = Some(chrono::Utc::now());
+            stored.integration.updated_at = chrono::Utc::now();
+
+            let stored_clone = stored.clone();
+            drop(integrations);
+            self.save_integration(&stored_clone).await?;
+
+            debug!("Spec of integration {} was not modified", key);
+            return Ok(SyncReport {
+                diff: SpecDiff::default(),
+                applied: true,
+                revision: stored_clone.integration.revision,
+            });
+        };
+        let hash = spec_hash(&fetched.content);
+
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        if stored.integration.spec_hash.as_ref() == Some(&hash) {
+            // Unchanged: only record that the spec was checked
+            stored.integration.last_synced_at = Some(chrono::Utc::now());
+            stored.integration.spec_etag = fetched.etag;
+            stored.integration.spec_last_modified = fetched.last_modified;
+            stored.integration.updated_at = chrono::Utc::now();
 
-            // Persist
             let stored_clone = stored.clone();
             drop(integrations);
             self.save_integration(&stored_clone).await?;
+
+            debug!("Spec of integration {} is unchanged", key);
+            return Ok(SyncReport {
+                diff,
+                applied: true,
+                revision: stored_clone.integration.revision,
+            });
+        }
+
+        if stored.integration.rejected_spec_hash.as_ref() == Some(&hash) {
+            info!("Skipping sync of {}: the remote spec was rolled back", key);
+            return Ok(SyncReport {
+                diff,
+                applied: false,
+                revision: stored.integration.revision,
+            });
+        }
+
+        if stored.integration.confirm_breaking_sync && diff.is_breaking() && !confirm {
+            info!(
+                "Holding back sync of {}: {} breaking changes need confirmation",
+                key,
+                diff.breaking_changes().count()
+            );
+            return Ok(SyncReport {
+                diff,
+                applied: false,
+                revision: stored.integration.revision,
+            });
+        }
+
+        let previous = SpecRevision::capture(stored);
+
+        // Update with new spec, preserving the user's configuration
+        let mut new_stored =
+            StoredIntegration::from_spec(key.to_string(), spec, Some(fetched.content));
+        let fresh = new_stored.integration;
+        new_stored.integration = Integration {
+            name: fresh.name,
+            description: fresh.description,
+            auth_scheme: fresh.auth_scheme,
+            operation_count: fresh.operation_count,
+            revision: previous.revision + 1,
+            spec_version: fresh.spec_version,
+            spec_hash: fresh.spec_hash,
+            rejected_spec_hash: None,
+            spec_etag: fetched.etag,
+            spec_last_modified: fetched.last_modified,
+            last_synced_at: fresh.last_synced_at,
+            updated_at: fresh.updated_at,
+            ..stored.integration.clone()
+        };
+
+        new_stored.overlay = stored.overlay.clone();
+        new_stored.assign_tool_names(Some(stored));
+        new_stored.refresh_server_url();
+        *stored = new_stored;
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_revision(key, &previous).await?;
+        self.save_integration(&stored_clone).await?;
+        self.index_tools().await;
+
+        info!(
+            "Synced integration {} to revision {} ({} changes, {} breaking)",
+            key,
+            stored_clone.integration.revision,
+            diff.changes.len(),
+            diff.breaking_changes().count()
+        );
+        Ok(SyncReport {
+            diff,
+            applied: true,
+            revision: stored_clone.integration.revision,
+        })
+    }
+
+    /// List an integration's spec versions, newest (the current one) first
+    pub async fn history(&self, key: &str) -> Result<Vec<SpecHistoryEntry>> {
+        let current = self
+            .get(key)
+            .await
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        let mut history = vec![current.history_entry()];
+        for revision in self.load_revisions(key).await? {
+            history.push(revision.summary());
+        }
+        Ok(history)
+    }
+
+    /// Restore an earlier spec version
+    ///
+    /// The restored spec becomes a new revision, so the rollback itself can
+    /// be undone. Until the remote spec changes again, syncs will not
+    /// re-apply the version that was rolled back.
+    pub async fn rollback(&self, key: &str, revision: u32) -> Result<SyncReport> {
+        let mut target = self.load_revision(key, revision).await?;
+
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        // Parse the restored spec again with the current overlay (if any)
+        if let Some(content) = &target.spec_content {
+            let spec = Self::parse_spec(
+                content,
+                stored.integration.spec_url.as_deref(),
+                stored.overlay.as_deref(),
+            )?;
+            target.auth_scheme = Some(AuthScheme::detect(
+                &spec.security_schemes,
+                &spec.global_security,
+            ));
+            target.name = spec.title;
+            target.description = spec.description;
+            target.spec_version = Some(spec.version);
+            target.operations = spec.operations;
+            target.security_schemes = spec.security_schemes;
+            target.servers = spec.servers;
+        }
+
+        let previous = SpecRevision::capture(stored);
+        let diff = SpecDiff::compute(
+            &stored.operations,
+            stored.integration.auth_scheme.as_ref(),
+            &target.operations,
+            target.auth_scheme.as_ref(),
+        );
+
+        let replaced = stored.clone();
+        let now = chrono::Utc::now();
+        stored.integration = Integration {
+            name: target.name,
+            description: target.description,
+            auth_scheme: target.auth_scheme,
+            operation_count: target.operations.len(),
+            revision: previous.revision + 1,
+            spec_version: target.spec_version,
+            spec_hash: target.spec_hash,
+            rejected_spec_hash: previous.spec_hash.clone(),
+            updated_at: now,
+            ..stored.integration.clone()
+        };
+        stored.namespace = NamespaceTree::build(&target.operations);
+        stored.operations = target.operations;
+        stored.spec_content = target.spec_content;
+        stored.security_schemes = target.security_schemes;
+        stored.servers = target.servers;
+        stored.assign_tool_names(Some(&replaced));
+        stored.refresh_server_url();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_revision(key, &previous).await?;
+        self.save_integration(&stored_clone).await?;
+        self.index_tools().await;
+
+        info!(
+            "Rolled back integration {} to revision {} (now revision {})",
+            key, revision, stored_clone.integration.revision
+        );
+        Ok(SyncReport {
+            diff,
+            applied: true,
+            revision: stored_clone.integration.revision,
+        })
+    }
+
+    /// Choose the server an integration calls and values for its URL variables
+    ///
+    /// `index` refers to the spec's server list. Variables left out use their
+    /// defaults; values must be allowed by the spec.
+    pub async fn select_server(
+        &self,
+        key: &str,
+        index: usize,
+        variables: BTreeMap<String, String>,
+    ) -> Result<Integration> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        if index >= stored.servers.len() {
+            return Err(WalletError::InvalidSpec(format!(
+                "{} has no server {}",
+                key, index
+            )));
         }
 
+        let mut updated = stored.clone();
+        updated.integration.server_index = index;
+        updated.integration.server_variables = variables;
+        updated.integration.server_url = updated.resolve_server_url()?;
+        updated.integration.server_error = None;
+        updated.integration.updated_at = chrono::Utc::now();
+        *stored = updated;
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        info!(
+            "Selected server {} for integration {}: {}",
+            index, key, stored_clone.integration.server_url
+        );
+        Ok(stored_clone.integration)
+    }
+
+    /// Set which operations of an integration are exposed as tools
+    pub async fn set_operation_filter(&self, key: &str, filter: OperationFilter) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        stored.integration.operation_filter = filter;
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Set operation filter for integration {} ({} of {} operations exposed)",
+            key,
+            stored_clone.allowed_operations().count(),
+            stored_clone.operations.len()
+        );
+        Ok(())
+    }
+
+    /// Attach an OpenAPI Overlay to an integration (or remove it with `None`)
+    ///
+    /// The stored spec is parsed again with the overlay applied, and the
+    /// overlay is re-applied to every synced spec. Returns how the
+    /// integration's operations changed.
+    pub async fn set_overlay(&self, key: &str, overlay: Option<String>) -> Result<SpecDiff> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        let content = stored.spec_content.as_deref().ok_or_else(|| {
+            WalletError::InvalidSpec(format!("{} has no stored spec to apply an overlay to", key))
+        })?;
+        let spec = Self::parse_spec(
+            content,
+            stored.integration.spec_url.as_deref(),
+            overlay.as_deref(),
+        )?;
+
+        let auth_scheme = AuthScheme::detect(&spec.security_schemes, &spec.global_security);
+        let diff = SpecDiff::compute(
+            &stored.operations,
+            stored.integration.auth_scheme.as_ref(),
+            &spec.operations,
+            Some(&auth_scheme),
+        );
+
+        let replaced = stored.clone();
+        stored.integration.name = spec.title;
+        stored.integration.description = spec.description;
+        stored.integration.auth_scheme = Some(auth_scheme);
+        stored.integration.operation_count = spec.operations.len();
+        stored.integration.spec_version = Some(spec.version);
+        stored.integration.updated_at = chrono::Utc::now();
+        stored.namespace = NamespaceTree::build(&spec.operations);
+        stored.operations = spec.operations;
+        stored.security_schemes = spec.security_schemes;
+        stored.servers = spec.servers;
+        stored.overlay = overlay;
+        stored.assign_tool_names(Some(&replaced));
+        stored.refresh_server_url();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+        self.index_tools().await;
+
+        info!(
+            "{} overlay of integration {} ({} changes)",
+            if stored_clone.overlay.is_some() {
+                "Set"
+            } else {
+                "Removed"
+            },
+            key,
+            diff.changes.len()
+        );
+        Ok(diff)
+    }
+
+    /// Require confirmation before applying syncs with breaking changes
+    pub async fn set_confirm_breaking_sync(&self, key: &str, confirm: bool) -> Result<()> {
+        let mut integrations = self.integrations.write().await;
+
+        let stored = integrations
+            .get_mut(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+
+        stored.integration.confirm_breaking_sync = confirm;
+        stored.integration.updated_at = chrono::Utc::now();
+
+        // Persist
+        let stored_clone = stored.clone();
+        drop(integrations);
+        self.save_integration(&stored_clone).await?;
+
+        debug!(
+            "Set breaking sync confirmation to {} for integration {}",
+            confirm, key
+        );
         Ok(())
     }
+
+    /// Fetch (or read from disk) and parse an integration's spec and diff it with the stored one
+    ///
+    /// A `conditional` fetch sends the stored cache validators and returns
+    /// `None` when the server reports the spec as not modified.
+    async fn fetch_remote(&self, key: &str, conditional: bool) -> Result<Option<RemoteSpec>> {
+        let (spec_url, spec_path, etag, last_modified, overlay) = {
+            let integrations = self.integrations.read().await;
+            let stored = integrations
+                .get(key)
+                .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+            let integration = &stored.integration;
+            (
+                integration.spec_url.clone(),
+                integration.spec_path.clone(),
+                integration.spec_etag.clone().filter(|_| conditional),
+                integration
+                    .spec_last_modified
+                    .clone()
+                    .filter(|_| conditional),
+                stored.overlay.clone(),
+            )
+        };
+
+        let fetched = match (&spec_url, spec_path) {
+            (Some(spec_url), _) => {
+                info!("Syncing integration: {} from {}", key, spec_url);
+
+                let Some(fetched) = OpenApiParser::fetch_if_modified(
+                    spec_url,
+                    etag.as_deref(),
+                    last_modified.as_deref(),
+                )
+                .await
+                .map_err(|e| WalletError::InvalidSpec(e.to_string()))?
+                else {
+                    return Ok(None);
+                };
+                fetched
+            }
+            (None, Some(spec_path)) => {
+                info!("Reloading integration: {} from {}", key, spec_path);
+
+                let local = OpenApiParser::load_file(Path::new(&spec_path))
+                    .map_err(|e| WalletError::InvalidSpec(e.to_string()))?;
+                FetchedSpec {
+                    content: local.content,
+                    etag: None,
+                    last_modified: None,
+                }
+            }
+            (None, None) => {
+                return Err(WalletError::IntegrationNotFound(format!(
+                    "{} has no spec URL or file",
+                    key
+                )))
+            }
+        };
+        let spec = Self::parse_spec(&fetched.content, spec_url.as_deref(), overlay.as_deref())?;
+
+        let integrations = self.integrations.read().await;
+        let stored = integrations
+            .get(key)
+            .ok_or_else(|| WalletError::IntegrationNotFound(key.to_string()))?;
+        let new_auth = AuthScheme::detect(&spec.security_schemes, &spec.global_security);
+        let diff = SpecDiff::compute(
+            &stored.operations,
+            stored.integration.auth_scheme.as_ref(),
+            &spec.operations,
+            Some(&new_auth),
+        );
+
+        Ok(Some(RemoteSpec {
+            spec,
+            fetched,
+            diff,
+        }))
+    }
+
+    /// Save a replaced spec revision, dropping revisions beyond the history limit
+    async fn save_revision(&self, key: &str, revision: &SpecRevision) -> Result<()> {
+        let storage_key = format!("{}{}:{}", REVISION_PREFIX, key, revision.revision);
+        let data = serde_json::to_vec(revision)?;
+        self.storage.store(&storage_key, &data).await?;
+
+        for (number, storage_key) in self.revision_keys(key).await? {
+            if number + MAX_REVISIONS <= revision.revision {
+                self.storage.delete(&storage_key).await?;
+                debug!("Dropped revision {} of integration {}", number, key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a replaced spec revision
+    async fn load_revision(&self, key: &str, revision: u32) -> Result<SpecRevision> {
+        let storage_key = format!("{}{}:{}", REVISION_PREFIX, key, revision);
+        let data = self.storage.retrieve(&storage_key).await?.ok_or_else(|| {
+            WalletError::IntegrationNotFound(format!("{} has no revision {}", key, revision))
+        })?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Load all replaced spec revisions, newest first
+    async fn load_revisions(&self, key: &str) -> Result<Vec<SpecRevision>> {
+        let mut revisions = Vec::new();
+        for (number, _) in self.revision_keys(key).await?.into_iter().rev() {
+            revisions.push(self.load_revision(key, number).await?);
+        }
+        Ok(revisions)
+    }
+
+    /// Storage keys of replaced spec revisions, oldest first
+    async fn revision_keys(&self, key: &str) -> Result<Vec<(u32, String)>> {
+        let prefix = format!("{}{}:", REVISION_PREFIX, key);
+        let mut keys: Vec<(u32, String)> = self
+            .storage
+            .list_keys(&prefix)
+            .await?
+            .into_iter()
+            .filter_map(|storage_key| {
+                let number = storage_key.strip_prefix(&prefix)?.parse().ok()?;
+                Some((number, storage_key))
+            })
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+/// The time `secs` seconds after `now`, or the latest representable time
+fn schedule_after(now: chrono::DateTime<chrono::Utc>, secs: u64) -> chrono::DateTime<chrono::Utc> {
+    i64::try_from(secs)
+        .ok()
+        .and_then(chrono::Duration::try_seconds)
+        .and_then(|delay| now.checked_add_signed(delay))
+        .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crypto::key_derivation::{derive_key, generate_salt};
+    use crate::crypto::{derive_key, generate_salt};
+    use crate::integration::SpecSource;
     use crate::storage::EncryptedFileStorage;
     use tempfile::TempDir;
 
-    async fn test_registry() -> IntegrationRegistry {
+    async fn test_registry() -> (IntegrationRegistry, TempDir) {
         let temp_dir = TempDir::new().unwrap();
         let storage = EncryptedFileStorage::with_dir(temp_dir.path().to_path_buf()).unwrap();
 
@@ -269,7 +1665,7 @@ mod tests {
         let key = derive_key("test", &salt, None).unwrap();
         storage.set_master_key(Some(key)).await;
 
-        IntegrationRegistry::new(Arc::new(storage))
+        (IntegrationRegistry::new(Arc::new(storage)), temp_dir)
     }
 
     const TEST_SPEC: &str = r#"
@@ -290,7 +1686,7 @@ paths:
 
     #[tokio::test]
     async fn test_add_from_content() {
-        let registry = test_registry().await;
+        let (registry, _temp) = test_registry().await;
 
         let integration = registry.add_from_content("test", TEST_SPEC).await.unwrap();
 
@@ -301,7 +1697,7 @@ paths:
 
     #[tokio::test]
     async fn test_list_operations() {
-        let registry = test_registry().await;
+        let (registry, _temp) = test_registry().await;
 
         registry.add_from_content("test", TEST_SPEC).await.unwrap();
 
@@ -310,9 +1706,224 @@ paths:
         assert_eq!(operations[0].operation_id, "listUsers");
     }
 
+    #[tokio::test]
+    async fn test_operation_filter() {
+        let (registry, _temp) = test_registry().await;
+
+        let spec = format!(
+            "{}    post:\n      operationId: createUser\n      responses:\n        '201':\n          description: Created\n",
+            TEST_SPEC
+        );
+        registry.add_from_content("test", &spec).await.unwrap();
+        assert_eq!(registry.list_operations("test").await.len(), 2);
+        let stored = registry.get_stored("test").await.unwrap();
+        assert!(stored.lookup_operation("create.user").is_some());
+
+        registry
+            .set_operation_filter("test", crate::integration::OperationFilter::read_only())
+            .await
+            .unwrap();
+        let operations = registry.list_operations("test").await;
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].operation_id, "listUsers");
+
+        let stored = registry.get_stored("test").await.unwrap();
+        assert_eq!(stored.operation_paths().len(), 1);
+        assert!(stored.lookup_operation("create.user").is_none());
+        assert_eq!(stored.operations.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_select_server() {
+        let (registry, _temp) = test_registry().await;
+
+        let spec = TEST_SPEC.replace(
+            "  - url: https://api.test.com\n",
+            "  - url: https://{region}.api.test.com\n    variables:\n      region:\n        default: us\n        enum: [us, eu]\n  - url: https://sandbox.test.com\n",
+        );
+        let integration = registry.add_from_content("test", &spec).await.unwrap();
+        assert_eq!(integration.server_url, "https://us.api.test.com");
+
+        let variables = BTreeMap::from([("region".to_string(), "eu".to_string())]);
+        let integration = registry
+            .select_server("test", 0, variables.clone())
+            .await
+            .unwrap();
+        assert_eq!(integration.server_url, "https://eu.api.test.com");
+
+        let invalid = BTreeMap::from([("region".to_string(), "ap".to_string())]);
+        assert!(registry.select_server("test", 0, invalid).await.is_err());
+        assert!(registry
+            .select_server("test", 2, BTreeMap::new())
+            .await
+            .is_err());
+        assert_eq!(
+            registry.get("test").await.unwrap().server_url,
+            "https://eu.api.test.com"
+        );
+
+        let integration = registry
+            .select_server("test", 1, BTreeMap::new())
+            .await
+            .unwrap();
+        assert_eq!(integration.server_url, "https://sandbox.test.com");
+    }
+
+    #[tokio::test]
+    async fn test_sync_keeps_server_no_longer_offered() {
+        let (registry, _temp) = test_registry().await;
+
+        let spec = TEST_SPEC.replace(
+            "  - url: https://api.test.com\n",
+            "  - url: https://api.test.com\n  - url: https://sandbox.test.com\n",
+        );
+        let (spec_url, served) = serve_spec(spec).await;
+        registry.add_from_url("test", &spec_url).await.unwrap();
+        registry
+            .select_server("test", 1, BTreeMap::new())
+            .await
+            .unwrap();
+
+        // The spec drops the chosen server
+        *served.lock().unwrap() = TEST_SPEC.to_string();
+        assert!(registry.sync("test", false).await.unwrap().applied);
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.server_url, "https://sandbox.test.com");
+        assert_eq!(integration.server_index, 1);
+        assert!(integration.server_error.is_some());
+
+        let integration = registry
+            .select_server("test", 0, BTreeMap::new())
+            .await
+            .unwrap();
+        assert_eq!(integration.server_url, "https://api.test.com");
+        assert!(integration.server_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_relative_server_resolves_against_spec_url() {
+        let (registry, _temp) = test_registry().await;
+
+        let spec = TEST_SPEC.replace("https://api.test.com", "/api/v2");
+        let (spec_url, served) = serve_spec(spec.clone()).await;
+
+        let integration = registry.add_from_url("test", &spec_url).await.unwrap();
+        let base = spec_url.trim_end_matches("/openapi.yaml");
+        assert_eq!(integration.server_url, format!("{}/api/v2", base));
+
+        // Still resolved after a sync that changes the spec
+        *served.lock().unwrap() = format!(
+            "{}  /teams:\n    get:\n      operationId: listTeams\n      responses:\n        '200':\n          description: OK\n",
+            spec
+        );
+        assert_eq!(registry.sync("test", false).await.unwrap().revision, 2);
+        assert_eq!(
+            registry.get("test").await.unwrap().server_url,
+            format!("{}/api/v2", base)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_from_template() {
+        let (registry, temp) = test_registry().await;
+
+        std::fs::write(temp.path().join("internal.yaml"), TEST_SPEC).unwrap();
+        std::fs::write(
+            temp.path().join("templates.json"),
+            r#"{"templates": [{
+                "name": "internal",
+                "title": "Internal API",
+                "key": "corp",
+                "provider": "corp-sso",
+                "spec": {"type": "file", "path": "internal.yaml"},
+                "auth": {"api_key": {"name": "X-Corp-Key", "location": "header"}},
+                "operation_filter": {"exclude": [{"type": "method", "method": "delete"}]}
+            }]}"#,
+        )
+        .unwrap();
+        let registry = registry.with_catalog_file(temp.path().join("templates.json"));
+
+        let catalog = registry.templates().unwrap();
+        assert!(catalog.get("slack").unwrap().is_builtin());
+        assert!(!catalog.get("internal").unwrap().is_builtin());
+
+        let integration = registry.add_from_template("internal").await.unwrap();
+        assert_eq!(integration.key, "corp");
+        assert_eq!(integration.credential_provider(), "corp-sso");
+        assert!(matches!(
+            integration.auth_override,
+            Some(AuthScheme::ApiKey { ref name, .. }) if name == "X-Corp-Key"
+        ));
+        assert_eq!(integration.operation_filter.exclude.len(), 1);
+
+        // An existing integration is not replaced
+        registry
+            .set_credential("corp", Uuid::new_v4())
+            .await
+            .unwrap();
+        assert!(matches!(
+            registry.add_from_template("internal").await,
+            Err(WalletError::InvalidIntegrationKey(_))
+        ));
+        assert!(registry.get("corp").await.unwrap().credential_id.is_some());
+
+        // The key is checked again when the integration is stored
+        let stored = StoredIntegration::from_spec(
+            "corp".to_string(),
+            OpenApiParser::parse(TEST_SPEC).unwrap(),
+            None,
+        );
+        assert!(matches!(
+            registry.insert("corp", stored, false).await,
+            Err(WalletError::InvalidIntegrationKey(_))
+        ));
+        assert!(registry.get("corp").await.unwrap().credential_id.is_some());
+
+        // Built-in templates with bundled specs work offline
+        let integration = registry.add_from_template("slack").await.unwrap();
+        assert_eq!(integration.server_url, "https://slack.com/api");
+        assert_eq!(integration.probe_operation.as_deref(), Some("auth.test"));
+
+        assert!(registry.add_from_template("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replacing_integration_keeps_tool_names() {
+        let (registry, _temp) = test_registry().await;
+
+        let operation = |id: &str| {
+            format!(
+                "      operationId: {}\n      responses:\n        '200':\n          description: OK\n",
+                id
+            )
+        };
+        let spec = |paths: &str| {
+            format!(
+                "openapi: \"3.0.0\"\ninfo:\n  title: Test API\n  version: \"1.0.0\"\npaths:\n{}",
+                paths
+            )
+        };
+        let v1 = format!(
+            "  /users:\n    get:\n{}  /users/all:\n    get:\n{}",
+            operation("list_users"),
+            operation("listUsers")
+        );
+        registry.add_from_content("test", &spec(&v1)).await.unwrap();
+
+        // A new operation taking the plain name does not rename existing tools
+        let v2 = format!("  /people:\n    get:\n{}{}", operation("list.users"), v1);
+        registry.add_from_content("test", &spec(&v2)).await.unwrap();
+
+        let stored = registry.get_stored("test").await.unwrap();
+        let (_, index) = registry.resolve_tool("test_list_users").await.unwrap();
+        assert_eq!(stored.operations[index].path, "/users");
+        let (_, index) = registry.resolve_tool("test_list_users_3").await.unwrap();
+        assert_eq!(stored.operations[index].path, "/people");
+    }
+
     #[tokio::test]
     async fn test_remove() {
-        let registry = test_registry().await;
+        let (registry, _temp) = test_registry().await;
 
         registry.add_from_content("test", TEST_SPEC).await.unwrap();
         assert!(registry.get("test").await.is_some());
@@ -320,4 +1931,761 @@ paths:
         registry.remove("test").await.unwrap();
         assert!(registry.get("test").await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_credential_field_mapping() {
+        let (registry, _temp) = test_registry().await;
+
+        let spec = format!(
+            "{}components:\n  securitySchemes:\n    appKeyAuth:\n      type: apiKey\n      in: header\n      name: X-App-Key\n    bearer:\n      type: http\n      scheme: bearer\n",
+            TEST_SPEC.trim_start()
+        );
+        registry.add_from_content("test", &spec).await.unwrap();
+
+        registry
+            .set_credential_field("test", "app", Some("appKeyAuth"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            registry
+                .set_credential_field("test", "token", Some("bearer"))
+                .await,
+            Err(WalletError::InvalidSpec(_))
+        ));
+
+        let stored = registry.get_stored("test").await.unwrap();
+        let targets = stored.credential_field_targets(&["app".to_string(), "unmapped".to_string()]);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].field, "app");
+        assert_eq!(targets[0].location, openapi_parser::ApiKeyLocation::Header);
+        assert_eq!(targets[0].name, "X-App-Key");
+
+        registry
+            .set_credential_field("test", "app", None)
+            .await
+            .unwrap();
+        let stored = registry.get_stored("test").await.unwrap();
+        assert!(stored
+            .credential_field_targets(&["app".to_string()])
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_auth_scheme_persisted_and_overridden() {
+        let temp = TempDir::new().unwrap();
+        let key = derive_key("test", &generate_salt(), None).unwrap();
+        let storage = EncryptedFileStorage::with_dir(temp.path().to_path_buf()).unwrap();
+        storage.set_master_key(Some(key.clone())).await;
+        let registry = IntegrationRegistry::new(Arc::new(storage));
+
+        let spec = format!(
+            "{}components:\n  securitySchemes:\n    apiKey:\n      type: apiKey\n      in: query\n      name: api_key\n",
+            TEST_SPEC.trim_start()
+        );
+        registry.add_from_content("test", &spec).await.unwrap();
+
+        let detected = AuthScheme::ApiKey {
+            name: "api_key".to_string(),
+            location: openapi_parser::ApiKeyLocation::Query,
+        };
+        assert_eq!(
+            registry.get_stored("test").await.unwrap().auth_scheme(),
+            detected
+        );
+
+        registry
+            .set_auth_override("test", Some(AuthScheme::Basic))
+            .await
+            .unwrap();
+
+        // Reload from storage
+        let storage = EncryptedFileStorage::with_dir(temp.path().to_path_buf()).unwrap();
+        storage.set_master_key(Some(key)).await;
+        storage.load().await.unwrap();
+        let reloaded = IntegrationRegistry::new(Arc::new(storage));
+        reloaded.load().await.unwrap();
+
+        let stored = reloaded.get_stored("test").await.unwrap();
+        assert_eq!(stored.integration.auth_scheme, Some(detected));
+        assert_eq!(stored.auth_scheme(), AuthScheme::Basic);
+
+        reloaded.set_auth_override("test", None).await.unwrap();
+        assert!(matches!(
+            reloaded.get_stored("test").await.unwrap().auth_scheme(),
+            AuthScheme::ApiKey { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_credential_bindings() {
+        let (registry, _temp) = test_registry().await;
+        registry.add_from_content("test", TEST_SPEC).await.unwrap();
+
+        let (test_id, live_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut test = CredentialBinding::new("test", test_id);
+        test.environment = Some("test".to_string());
+        registry.bind_credential("test", test, false).await.unwrap();
+        registry
+            .bind_credential("test", CredentialBinding::new("live", live_id), true)
+            .await
+            .unwrap();
+
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.credential_id, Some(live_id));
+        assert_eq!(integration.status, IntegrationStatus::Active);
+        assert_eq!(
+            integration.select_binding(None).unwrap().unwrap().label,
+            "live"
+        );
+        assert_eq!(
+            integration
+                .select_binding(Some("test"))
+                .unwrap()
+                .unwrap()
+                .credential_id,
+            test_id
+        );
+        assert!(matches!(
+            integration.select_binding(Some("staging")),
+            Err(WalletError::CredentialNotFound(_))
+        ));
+
+        // The active environment beats the default
+        registry
+            .set_environment("test", Some("test"))
+            .await
+            .unwrap();
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(
+            integration.select_binding(None).unwrap().unwrap().label,
+            "test"
+        );
+
+        // Removing the default promotes the remaining binding
+        registry.unbind_credential("test", "live").await.unwrap();
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.credential_id, Some(test_id));
+
+        registry.unbind_credential("test", "test").await.unwrap();
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.credential_id, None);
+        assert_eq!(integration.status, IntegrationStatus::Pending);
+
+        // Binding does not re-enable a disabled integration
+        registry.set_enabled("test", false).await.unwrap();
+        registry
+            .bind_credential("test", CredentialBinding::new("live", live_id), true)
+            .await
+            .unwrap();
+        assert_eq!(
+            registry.get("test").await.unwrap().status,
+            IntegrationStatus::Disabled
+        );
+        registry.unbind_credential("test", "live").await.unwrap();
+        assert_eq!(
+            registry.get("test").await.unwrap().status,
+            IntegrationStatus::Disabled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_operation() {
+        let (registry, _temp) = test_registry().await;
+        let spec = r#"
+openapi: "3.0.0"
+info:
+  title: Test API
+  version: "1.0.0"
+paths:
+  /accounts/{id}:
+    get:
+      operationId: getAccount
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        '200':
+          description: OK
+  /projects:
+    get:
+      operationId: listProjects
+      responses:
+        '200':
+          description: OK
+  /user:
+    get:
+      operationId: getAuthenticatedUser
+      responses:
+        '200':
+          description: OK
+"#;
+        registry.add_from_content("test", spec).await.unwrap();
+
+        let stored = registry.get_stored("test").await.unwrap();
+        let probe = stored.probe_operation().unwrap();
+        assert_eq!(stored.lookup_operation(&probe).unwrap().path, "/user");
+
+        assert!(matches!(
+            registry.set_probe_operation("test", Some("missing")).await,
+            Err(WalletError::OperationNotFound(_))
+        ));
+        let projects = stored
+            .operation_paths()
+            .into_iter()
+            .find(|p| stored.lookup_operation(p).unwrap().path == "/projects")
+            .unwrap();
+        registry
+            .set_probe_operation("test", Some(&projects))
+            .await
+            .unwrap();
+        let stored = registry.get_stored("test").await.unwrap();
+        assert_eq!(stored.probe_operation(), Some(projects));
+
+        registry
+            .set_health_check_interval("test", Some(3600))
+            .await
+            .unwrap();
+        let integration = registry
+            .record_validation("test", Some("No credential".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        assert!(integration.last_validated_at.is_some());
+        assert!(integration.next_health_check_at.unwrap() > chrono::Utc::now());
+
+        // The probe's response is recorded as request health
+        registry
+            .record_health("test", &HealthOutcome::Healthy)
+            .await
+            .unwrap();
+        let integration = registry.record_validation("test", None).await.unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Active);
+        assert_eq!(integration.error, None);
+    }
+
+    /// Serve a spec that the test can replace
+    async fn serve_spec(spec: String) -> (String, Arc<std::sync::Mutex<String>>) {
+        use axum::{extract::State, routing::get, Router};
+
+        let served = Arc::new(std::sync::Mutex::new(spec));
+        let app = Router::new()
+            .route(
+                "/openapi.yaml",
+                get(
+                    |State(served): State<Arc<std::sync::Mutex<String>>>| async move {
+                        served.lock().unwrap().clone()
+                    },
+                ),
+            )
+            .with_state(served.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let spec_url = format!("http://{}/openapi.yaml", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (spec_url, served)
+    }
+
+    #[tokio::test]
+    async fn test_sync_reports_diff_and_keeps_revision() {
+        let v1 = format!(
+            "{}  /users/{{id}}:\n    delete:\n      operationId: deleteUser\n      responses:\n        '204':\n          description: Deleted\n",
+            TEST_SPEC
+        );
+        let v2 = format!(
+            "{}  /teams:\n    get:\n      operationId: listTeams\n      responses:\n        '200':\n          description: OK\n",
+            TEST_SPEC
+        );
+
+        let (spec_url, served) = serve_spec(v1).await;
+
+        let (registry, _temp) = test_registry().await;
+        registry.add_from_url("test", &spec_url).await.unwrap();
+        registry
+            .set_probe_operation("test", Some("list.users"))
+            .await
+            .unwrap();
+
+        *served.lock().unwrap() = v2;
+
+        let diff = registry.preview_sync("test").await.unwrap();
+        assert_eq!(diff.removed_tools(), vec!["delete.user"]);
+        assert!(diff.is_breaking());
+
+        // Breaking syncs wait for confirmation when required
+        registry
+            .set_confirm_breaking_sync("test", true)
+            .await
+            .unwrap();
+        let report = registry.sync("test", false).await.unwrap();
+        assert!(!report.applied);
+        assert_eq!(report.revision, 1);
+        assert!(registry
+            .lookup_operation("test", "delete.user")
+            .await
+            .is_some());
+
+        let report = registry.sync("test", true).await.unwrap();
+        assert!(report.applied);
+        assert_eq!(report.revision, 2);
+        assert_eq!(report.diff, diff);
+
+        let stored = registry.get_stored("test").await.unwrap();
+        assert!(stored.lookup_operation("delete.user").is_none());
+        assert!(stored.lookup_operation("list.teams").is_some());
+        assert!(stored.spec_content.unwrap().contains("listTeams"));
+        assert_eq!(
+            stored.integration.probe_operation.as_deref(),
+            Some("list.users")
+        );
+
+        // The replaced spec is kept
+        let data = registry
+            .storage
+            .retrieve("integration_revision:test:1")
+            .await
+            .unwrap()
+            .unwrap();
+        let previous: SpecRevision = serde_json::from_slice(&data).unwrap();
+        assert_eq!(previous.operations.len(), 2);
+
+        registry.remove("test").await.unwrap();
+        assert!(registry
+            .storage
+            .list_keys(REVISION_PREFIX)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_and_rollback() {
+        let good = TEST_SPEC.to_string();
+        let bad = TEST_SPEC.replace("listUsers", "getUsers");
+        let (spec_url, served) = serve_spec(good).await;
+
+        let (registry, _temp) = test_registry().await;
+        registry.add_from_url("test", &spec_url).await.unwrap();
+
+        // Unchanged specs do not create revisions
+        let report = registry.sync("test", false).await.unwrap();
+        assert!(report.diff.is_empty());
+        assert_eq!(report.revision, 1);
+
+        *served.lock().unwrap() = bad;
+        let report = registry.sync("test", false).await.unwrap();
+        assert_eq!(report.revision, 2);
+        assert!(registry
+            .lookup_operation("test", "get.users")
+            .await
+            .is_some());
+
+        let history = registry.history("test").await.unwrap();
+        assert_eq!(
+            history.iter().map(|e| e.revision).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(history[0].replaced_at, None);
+        assert_eq!(history[1].spec_version.as_deref(), Some("1.0.0"));
+        assert_eq!(
+            history[1].source,
+            SpecSource::Url {
+                url: spec_url.clone()
+            }
+        );
+        assert_ne!(history[0].spec_hash, history[1].spec_hash);
+
+        let report = registry.rollback("test", 1).await.unwrap();
+        assert_eq!(report.revision, 3);
+        assert!(report.diff.is_breaking());
+        assert!(registry
+            .lookup_operation("test", "list.users")
+            .await
+            .is_some());
+        assert!(registry
+            .lookup_operation("test", "get.users")
+            .await
+            .is_none());
+
+        // The rolled back spec is not applied again until it changes
+        let report = registry.sync("test", false).await.unwrap();
+        assert!(!report.applied);
+        assert!(registry
+            .lookup_operation("test", "list.users")
+            .await
+            .is_some());
+
+        *served.lock().unwrap() = TEST_SPEC.replace("listUsers", "fetchUsers");
+        let report = registry.sync("test", false).await.unwrap();
+        assert!(report.applied);
+        assert!(registry
+            .lookup_operation("test", "fetch.users")
+            .await
+            .is_some());
+
+        assert!(registry.rollback("test", 42).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_history_is_bounded() {
+        let (registry, _temp) = test_registry().await;
+
+        for version in 0..MAX_REVISIONS + 5 {
+            let spec = TEST_SPEC.replace("1.0.0", &format!("1.0.{}", version));
+            registry.add_from_content("test", &spec).await.unwrap();
+        }
+
+        let history = registry.history("test").await.unwrap();
+        assert_eq!(history.len(), MAX_REVISIONS as usize + 1);
+        assert_eq!(history[0].revision, MAX_REVISIONS + 5);
+        assert_eq!(history[0].source, SpecSource::Content);
+        assert_eq!(history.last().unwrap().revision, 5);
+    }
+
+    /// Spec stub that answers `If-None-Match` with 304 and can be made to fail
+    #[derive(Default)]
+    struct ConditionalStub {
+        spec: std::sync::Mutex<String>,
+        failing: std::sync::atomic::AtomicBool,
+        full: std::sync::atomic::AtomicUsize,
+        not_modified: std::sync::atomic::AtomicUsize,
+    }
+
+    async fn serve_conditional(spec: &str) -> (String, Arc<ConditionalStub>) {
+        use axum::{
+            extract::State,
+            http::{header, HeaderMap, StatusCode},
+            response::IntoResponse,
+            routing::get,
+            Router,
+        };
+        use std::sync::atomic::Ordering;
+
+        async fn handler(
+            State(stub): State<Arc<ConditionalStub>>,
+            headers: HeaderMap,
+        ) -> axum::response::Response {
+            if stub.failing.load(Ordering::SeqCst) {
+                return StatusCode::BAD_GATEWAY.into_response();
+            }
+            let spec = stub.spec.lock().unwrap().clone();
+            let etag = format!("\"{}\"", spec_hash(&spec));
+            if headers
+                .get(header::IF_NONE_MATCH)
+                .and_then(|v| v.to_str().ok())
+                == Some(etag.as_str())
+            {
+                stub.not_modified.fetch_add(1, Ordering::SeqCst);
+                return StatusCode::NOT_MODIFIED.into_response();
+            }
+            stub.full.fetch_add(1, Ordering::SeqCst);
+            ([(header::ETAG, etag)], spec).into_response()
+        }
+
+        let stub = Arc::new(ConditionalStub {
+            spec: std::sync::Mutex::new(spec.to_string()),
+            ..Default::default()
+        });
+        let app = Router::new()
+            .route("/openapi.yaml", get(handler))
+            .with_state(stub.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let spec_url = format!("http://{}/openapi.yaml", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (spec_url, stub)
+    }
+
+    /// Make an integration's background sync due now
+    async fn make_due(registry: &IntegrationRegistry, key: &str) {
+        let mut integrations = registry.integrations.write().await;
+        integrations.get_mut(key).unwrap().integration.next_sync_at =
+            Some(chrono::Utc::now() - chrono::Duration::seconds(1));
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_sync_uses_conditional_requests() {
+        use std::sync::atomic::Ordering;
+
+        let (registry, _temp) = test_registry().await;
+        let (spec_url, stub) = serve_conditional(TEST_SPEC).await;
+
+        let integration = registry.add_from_url("test", &spec_url).await.unwrap();
+        assert!(integration.spec_etag.is_some());
+        assert!(matches!(
+            registry.set_sync_interval("test", Some(10)).await,
+            Err(WalletError::InvalidSpec(_))
+        ));
+        registry
+            .set_sync_interval("test", Some(3600))
+            .await
+            .unwrap();
+
+        // Not due until the interval has passed
+        assert!(registry.sync_due().await.is_empty());
+
+        make_due(&registry, "test").await;
+        let results = registry.sync_due().await;
+        assert_eq!(results.len(), 1);
+        let report = results[0].1.as_ref().unwrap();
+        assert!(report.applied && report.diff.is_empty());
+        assert_eq!(stub.full.load(Ordering::SeqCst), 1);
+        assert_eq!(stub.not_modified.load(Ordering::SeqCst), 1);
+
+        let integration = registry.get("test").await.unwrap();
+        assert!(integration.next_sync_at.unwrap() > chrono::Utc::now());
+        assert!(registry.sync_due().await.is_empty());
+
+        // A changed spec is downloaded and applied
+        *stub.spec.lock().unwrap() = format!(
+            "{}  /teams:\n    get:\n      operationId: listTeams\n      responses:\n        '200':\n          description: OK\n",
+            TEST_SPEC
+        );
+        make_due(&registry, "test").await;
+        let results = registry.sync_due().await;
+        let report = results[0].1.as_ref().unwrap();
+        assert!(report.applied);
+        assert_eq!(report.revision, 2);
+        assert_eq!(stub.full.load(Ordering::SeqCst), 2);
+        assert_eq!(registry.get("test").await.unwrap().operation_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sync_reloads_changes_of_other_processes() {
+        use std::sync::atomic::Ordering;
+
+        let (registry, _temp) = test_registry().await;
+        let (spec_url, stub) = serve_conditional(TEST_SPEC).await;
+        registry.add_from_url("test", &spec_url).await.unwrap();
+        registry
+            .set_sync_interval("test", Some(3600))
+            .await
+            .unwrap();
+
+        // A second process with its own cache of the same storage
+        let other = IntegrationRegistry::new(registry.storage.clone());
+        other.load().await.unwrap();
+
+        // Its changes survive a sync here
+        let credential_id = Uuid::new_v4();
+        other.set_credential("test", credential_id).await.unwrap();
+        registry.sync("test", false).await.unwrap();
+        assert_eq!(
+            registry.get("test").await.unwrap().credential_id,
+            Some(credential_id)
+        );
+
+        // An integration synced here is no longer due there
+        make_due(&registry, "test").await;
+        make_due(&other, "test").await;
+        assert_eq!(registry.sync_due().await.len(), 1);
+        let fetches = stub.not_modified.load(Ordering::SeqCst);
+        assert!(other.sync_due().await.is_empty());
+        assert_eq!(stub.not_modified.load(Ordering::SeqCst), fetches);
+        assert_eq!(
+            other.get("test").await.unwrap().credential_id,
+            Some(credential_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overlay_survives_sync() {
+        const OVERLAY: &str = r#"
+overlay: 1.0.0
+info:
+  title: Local fixes
+  version: "1"
+actions:
+  - target: $.servers[0]
+    update:
+      url: https://eu.test.com
+  - target: $.paths['/users'].get
+    update:
+      summary: List all users
+  - target: $.paths['/teams']
+    remove: true
+"#;
+
+        let (registry, _temp) = test_registry().await;
+        let (spec_url, stub) = serve_conditional(TEST_SPEC).await;
+        registry.add_from_url("test", &spec_url).await.unwrap();
+
+        assert!(matches!(
+            registry
+                .set_overlay("test", Some("overlay: 1.0.0\nactions: []".to_string()))
+                .await,
+            Err(WalletError::InvalidSpec(_))
+        ));
+
+        registry
+            .set_overlay("test", Some(OVERLAY.to_string()))
+            .await
+            .unwrap();
+        let summary = |registry: &IntegrationRegistry| {
+            let integrations = registry.integrations.try_read().unwrap();
+            integrations["test"].operations[0].summary.clone()
+        };
+        assert_eq!(
+            registry.get("test").await.unwrap().server_url,
+            "https://eu.test.com"
+        );
+        assert_eq!(summary(&registry).as_deref(), Some("List all users"));
+
+        // The overlay is applied to the synced spec as well
+        *stub.spec.lock().unwrap() = format!(
+            "{}  /teams:\n    get:\n      operationId: listTeams\n      responses:\n        '200':\n          description: OK\n",
+            TEST_SPEC
+        );
+        let report = registry.sync("test", false).await.unwrap();
+        assert!(report.applied && report.diff.is_empty());
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.revision, 2);
+        assert_eq!(integration.operation_count, 1);
+        assert_eq!(integration.server_url, "https://eu.test.com");
+        assert_eq!(summary(&registry).as_deref(), Some("List all users"));
+
+        // Removing the overlay restores the spec as published
+        let diff = registry.set_overlay("test", None).await.unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.operation_count, 2);
+        assert_eq!(integration.server_url, "https://api.test.com");
+
+        // A revision saved with the overlay is restored without it
+        registry.rollback("test", 1).await.unwrap();
+        assert_eq!(summary(&registry), None);
+        assert_eq!(
+            registry.get("test").await.unwrap().server_url,
+            "https://api.test.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_from_path_reloads_changed_files() {
+        let (registry, temp) = test_registry().await;
+        let dir = temp.path().join("spec");
+        std::fs::create_dir(&dir).unwrap();
+        let spec_file = dir.join("openapi.yaml");
+        std::fs::write(
+            &spec_file,
+            TEST_SPEC.replace(
+                "        '200':\n          description: OK\n",
+                "        '200':\n          $ref: \"responses.yaml#/Users\"\n",
+            ),
+        )
+        .unwrap();
+        let users = "Users:\n  description: OK\n";
+        std::fs::write(dir.join("responses.yaml"), users).unwrap();
+
+        let integration = registry.add_from_path("test", &spec_file).await.unwrap();
+        assert_eq!(integration.operation_count, 1);
+        assert!(matches!(
+            integration.spec_source(),
+            SpecSource::File { path } if path.ends_with("openapi.yaml")
+        ));
+        assert!(registry.reload_changed_files().await.is_empty());
+
+        // A change to a referenced file is picked up
+        std::fs::write(
+            dir.join("responses.yaml"),
+            "Users:\n  description: The users\n",
+        )
+        .unwrap();
+        let results = registry.reload_changed_files().await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.as_ref().unwrap().revision, 2);
+        assert!(registry.reload_changed_files().await.is_empty());
+
+        // A broken file is reported once, then reloaded when it is fixed
+        std::fs::remove_file(dir.join("responses.yaml")).unwrap();
+        let results = registry.reload_changed_files().await;
+        assert!(results[0].1.is_err());
+        assert!(registry.reload_changed_files().await.is_empty());
+
+        // Restoring the missing file is enough
+        std::fs::write(dir.join("responses.yaml"), users).unwrap();
+        let results = registry.reload_changed_files().await;
+        assert_eq!(results[0].1.as_ref().unwrap().revision, 3);
+        assert!(registry.reload_changed_files().await.is_empty());
+
+        std::fs::write(
+            &spec_file,
+            format!(
+                "{}  /teams:\n    get:\n      operationId: listTeams\n      responses:\n        '200':\n          $ref: \"responses.yaml#/Users\"\n",
+                std::fs::read_to_string(&spec_file).unwrap()
+            ),
+        )
+        .unwrap();
+        let results = registry.reload_changed_files().await;
+        assert!(results[0].1.is_ok());
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.operation_count, 2);
+        assert_eq!(integration.revision, 4);
+        assert_eq!(integration.sync_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_sync_backs_off() {
+        use std::sync::atomic::Ordering;
+
+        let (registry, _temp) = test_registry().await;
+        let (spec_url, stub) = serve_conditional(TEST_SPEC).await;
+
+        registry.add_from_url("test", &spec_url).await.unwrap();
+        registry
+            .set_sync_interval("test", Some(3600))
+            .await
+            .unwrap();
+        stub.failing.store(true, Ordering::SeqCst);
+
+        for failures in 1..=2u32 {
+            make_due(&registry, "test").await;
+            let before = chrono::Utc::now();
+            let results = registry.sync_due().await;
+            assert!(results[0].1.is_err());
+
+            let integration = registry.get("test").await.unwrap();
+            assert_eq!(integration.sync_failures, failures);
+            assert!(integration.error.as_ref().unwrap().contains("502"));
+            let delay = integration.next_sync_at.unwrap() - before;
+            assert!(delay >= chrono::Duration::seconds(3600 << failures));
+            assert!(delay < chrono::Duration::seconds((3600 << failures) + 60));
+        }
+
+        // Recovery clears the error and the backoff
+        stub.failing.store(false, Ordering::SeqCst);
+        make_due(&registry, "test").await;
+        assert!(registry.sync_due().await[0].1.is_ok());
+
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.sync_failures, 0);
+        assert_eq!(integration.error, None);
+        let delay = integration.next_sync_at.unwrap() - chrono::Utc::now();
+        assert!(delay <= chrono::Duration::seconds(3600));
+
+        // Syncs leave errors they did not write alone
+        registry
+            .record_health("test", &HealthOutcome::AuthFailed(401))
+            .await
+            .unwrap();
+        stub.failing.store(true, Ordering::SeqCst);
+        make_due(&registry, "test").await;
+        assert!(registry.sync_due().await[0].1.is_err());
+        stub.failing.store(false, Ordering::SeqCst);
+        make_due(&registry, "test").await;
+        assert!(registry.sync_due().await[0].1.is_ok());
+
+        let integration = registry.get("test").await.unwrap();
+        assert_eq!(integration.status, IntegrationStatus::Error);
+        assert_eq!(
+            integration.error.as_deref(),
+            Some("Credential rejected (401)")
+        );
+    }
 }
@@ -0,0 +1,205 @@
+//! Catalog of integration templates for well-known APIs
+//!
+//! A template says where a provider's spec lives and how to set the
+//! integration up: which credential provider goes with it, the
+//! authentication scheme, recommended operation filters and the server to
+//! use. Built-in templates are bundled with the crate; a user catalog file
+//! in the same format adds templates (for internal APIs) or replaces
+//! built-in ones with the same name.
+
+use openapi_parser::AuthScheme;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::filter::OperationFilter;
+use super::types::StoredIntegration;
+use crate::error::{Result, WalletError};
+
+/// Built-in catalog
+const BUILTIN_CATALOG: &str = include_str!("../../templates/catalog.json");
+
+/// Spec files bundled with the built-in catalog
+const BUILTIN_SPECS: &[(&str, &str)] =
+    &[("slack.yaml", include_str!("../../templates/slack.yaml"))];
+
+/// Where a template's spec comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateSpec {
+    /// Spec fetched from a URL
+    Url { url: String },
+    /// Spec file, relative to the catalog file
+    File { path: String },
+}
+
+/// How to set up an integration for a well-known API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationTemplate {
+    /// Template name (e.g., "github")
+    pub name: String,
+
+    /// Human-readable name
+    pub title: String,
+
+    /// Description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Integration key (defaults to the template name)
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// Credential provider whose credentials fit the integration
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    /// Where the spec comes from
+    pub spec: TemplateSpec,
+
+    /// Authentication scheme to use instead of the detected one
+    #[serde(default)]
+    pub auth: Option<AuthScheme>,
+
+    /// Multi-field credential mappings (field name -> apiKey security scheme name)
+    #[serde(default)]
+    pub credential_fields: BTreeMap<String, String>,
+
+    /// Recommended operation filter
+    #[serde(default)]
+    pub operation_filter: OperationFilter,
+
+    /// Index of the server to use among the spec's servers
+    #[serde(default)]
+    pub server_index: usize,
+
+    /// Values for the server's URL variables
+    #[serde(default)]
+    pub server_variables: BTreeMap<String, String>,
+
+    /// Operation called to check that a credential works
+    #[serde(default)]
+    pub probe_operation: Option<String>,
+
+    /// Directory of the catalog file the template came from (`None` when built in)
+    #[serde(skip)]
+    catalog_dir: Option<PathBuf>,
+}
+
+impl IntegrationTemplate {
+    /// Integration key the template creates
+    pub fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Whether the template is bundled with the wallet
+    pub fn is_builtin(&self) -> bool {
+        self.catalog_dir.is_none()
+    }
+
+    /// Read a file spec (bundled, or relative to the user catalog)
+    pub fn read_spec_file(&self, path: &str) -> Result<String> {
+        match &self.catalog_dir {
+            None => BUILTIN_SPECS
+                .iter()
+                .find(|(name, _)| *name == path)
+                .map(|(_, content)| content.to_string())
+                .ok_or_else(|| WalletError::InvalidSpec(format!("No bundled spec named {}", path))),
+            Some(dir) => {
+                let path = dir.join(path);
+                std::fs::read_to_string(&path).map_err(|e| {
+                    WalletError::InvalidSpec(format!("Failed to read {}: {}", path.display(), e))
+                })
+            }
+        }
+    }
+
+    /// Apply the template's settings to a newly created integration
+    pub fn apply(&self, stored: &mut StoredIntegration) -> Result<()> {
+        let integration = &mut stored.integration;
+        if let Some(auth) = &self.auth {
+            integration.auth_override = Some(auth.clone());
+        }
+        integration.provider = self.provider.clone();
+        integration.credential_fields = self.credential_fields.clone();
+        integration.operation_filter = self.operation_filter.clone();
+        integration.probe_operation = self.probe_operation.clone();
+        integration.server_index = self.server_index;
+        integration.server_variables = self.server_variables.clone();
+        stored.integration.server_url = stored.resolve_server_url()?;
+        Ok(())
+    }
+}
+
+/// A list of templates, as stored in a catalog file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateCatalog {
+    /// Templates in the catalog
+    pub templates: Vec<IntegrationTemplate>,
+}
+
+impl TemplateCatalog {
+    /// The built-in catalog
+    pub fn builtin() -> Self {
+        serde_json::from_str(BUILTIN_CATALOG).expect("built-in catalog is valid")
+    }
+
+    /// Load a user catalog file; file specs are relative to its directory
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        let mut catalog: Self = serde_json::from_str(&data).map_err(|e| {
+            WalletError::ParseError(format!("Invalid catalog {}: {}", path.display(), e))
+        })?;
+
+        let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        for template in &mut catalog.templates {
+            template.catalog_dir = Some(dir.clone());
+        }
+        Ok(catalog)
+    }
+
+    /// Add templates, replacing those with the same name
+    pub fn merge(&mut self, other: TemplateCatalog) {
+        for template in other.templates {
+            self.templates.retain(|t| t.name != template.name);
+            self.templates.push(template);
+        }
+        self.templates.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Find a template by name
+    pub fn get(&self, name: &str) -> Option<&IntegrationTemplate> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openapi_parser::OpenApiParser;
+
+    #[test]
+    fn test_builtin_catalog() {
+        let catalog = TemplateCatalog::builtin();
+
+        for name in ["github", "openai", "slack", "stripe"] {
+            let template = catalog.get(name).unwrap();
+            assert!(template.is_builtin());
+            assert_eq!(template.provider.as_deref(), Some(name));
+        }
+
+        // Bundled specs parse and their templates apply cleanly
+        for template in &catalog.templates {
+            if let TemplateSpec::File { path } = &template.spec {
+                let content = template.read_spec_file(path).unwrap();
+                let spec = OpenApiParser::parse(&content).unwrap();
+                let mut stored =
+                    StoredIntegration::from_spec(template.key().to_string(), spec, Some(content));
+                template.apply(&mut stored).unwrap();
+                if let Some(probe) = &template.probe_operation {
+                    assert!(stored.lookup_operation(probe).is_some());
+                }
+            }
+        }
+    }
+}
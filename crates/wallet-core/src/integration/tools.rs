@@ -0,0 +1,214 @@
+//! Tool names of integration operations
+//!
+//! Each operation is exposed as the tool `{key}_{normalized id}`, with the
+//! dots of the normalized ID replaced by underscores. Integration keys may
+//! not contain underscores, so tool names of different integrations never
+//! collide. Operations of one integration whose names still do (e.g.,
+//! `list_users` and `list.users`) get a numeric suffix, and a synced spec
+//! keeps the names its operations already had.
+
+use openapi_parser::{ApiOperation, HttpMethod};
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+use super::types::StoredIntegration;
+use crate::error::{Result, WalletError};
+
+/// Check that a key can be used for an integration
+///
+/// Keys are ASCII letters, digits and hyphens, starting with a letter or digit.
+pub fn validate_key(key: &str) -> Result<()> {
+    let mut chars = key.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(WalletError::InvalidIntegrationKey(format!(
+            "{:?} (use letters, digits and hyphens)",
+            key
+        )))
+    }
+}
+
+/// Tool name of an operation before collisions are resolved
+pub fn tool_name(key: &str, operation: &ApiOperation) -> String {
+    format!("{}_{}", key, operation.normalized_id.replace('.', "_"))
+}
+
+/// Whether `name` is `plain` or `plain` with a numeric suffix
+fn is_variant(name: &str, plain: &str) -> bool {
+    match name.strip_prefix(plain) {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('_')
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+        None => false,
+    }
+}
+
+impl StoredIntegration {
+    /// Give each operation a tool name
+    ///
+    /// Operations with the same method and path as in `previous` keep their
+    /// name; the others take their plain name, or a numeric suffix when it
+    /// is taken. The suffixed names are recorded as collisions.
+    pub fn assign_tool_names(&mut self, previous: Option<&StoredIntegration>) {
+        let key = &self.integration.key;
+        let kept: HashMap<(HttpMethod, &str), &str> = previous
+            .into_iter()
+            .flat_map(|previous| {
+                previous
+                    .operations
+                    .iter()
+                    .zip(&previous.tool_names)
+                    .map(|(op, name)| ((op.method, op.path.as_str()), name.as_str()))
+            })
+            .collect();
+
+        let mut names: Vec<Option<String>> = vec![None; self.operations.len()];
+        let mut taken = HashSet::new();
+
+        // Names operations already had come first
+        for (index, operation) in self.operations.iter().enumerate() {
+            let plain = tool_name(key, operation);
+            if let Some(name) = kept.get(&(operation.method, operation.path.as_str())) {
+                if is_variant(name, &plain) && taken.insert(name.to_string()) {
+                    names[index] = Some(name.to_string());
+                }
+            }
+        }
+
+        for (index, operation) in self.operations.iter().enumerate() {
+            if names[index].is_some() {
+                continue;
+            }
+
+            let plain = tool_name(key, operation);
+            let mut name = plain.clone();
+            let mut suffix = 2;
+            while !taken.insert(name.clone()) {
+                name = format!("{}_{}", plain, suffix);
+                suffix += 1;
+            }
+            if name != plain {
+                warn!(
+                    "Tool name {} of {} {} is taken, using {}",
+                    plain, operation.method, operation.path, name
+                );
+            }
+            names[index] = Some(name);
+        }
+
+        self.tool_names = names.into_iter().flatten().collect();
+        let mut collisions: Vec<String> = self
+            .tool_names
+            .iter()
+            .zip(&self.operations)
+            .filter(|(name, op)| **name != tool_name(key, op))
+            .map(|(name, _)| name.clone())
+            .collect();
+        collisions.sort();
+        self.integration.tool_name_collisions = collisions;
+    }
+
+    /// Operations exposed as tools, with their tool names
+    pub fn allowed_tools(&self) -> impl Iterator<Item = (&str, &ApiOperation)> {
+        self.tool_names
+            .iter()
+            .map(String::as_str)
+            .zip(&self.operations)
+            .filter(|(_, op)| self.integration.operation_filter.allows(op))
+    }
+
+    /// The operation behind a tool, if the tool still names it and it is exposed
+    pub fn tool_operation(&self, index: usize, tool_name: &str) -> Option<&ApiOperation> {
+        if self.tool_names.get(index).map(String::as_str) != Some(tool_name) {
+            return None;
+        }
+        self.operations
+            .get(index)
+            .filter(|op| self.integration.operation_filter.allows(op))
+    }
+
+    /// Tool name of the operation at a namespace path
+    pub fn operation_tool_name(&self, path: &str) -> Option<&str> {
+        let index = self.namespace.lookup(path)?.index;
+        self.lookup_operation(path)?;
+        self.tool_names.get(index).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openapi_parser::OpenApiParser;
+
+    fn stored(paths: &str) -> StoredIntegration {
+        let spec = format!(
+            "openapi: \"3.0.0\"\ninfo:\n  title: Test\n  version: \"1\"\npaths:\n{}",
+            paths
+        );
+        let spec = OpenApiParser::parse(&spec).unwrap();
+        StoredIntegration::from_spec("test".to_string(), spec, None)
+    }
+
+    fn operation(id: &str) -> String {
+        format!(
+            "      operationId: {}\n      responses:\n        '200':\n          description: OK\n",
+            id
+        )
+    }
+
+    #[test]
+    fn test_validate_key() {
+        for key in ["github", "my-api", "v2"] {
+            assert!(validate_key(key).is_ok());
+        }
+        for key in ["", "my_api", "-api", "a.b", "api key"] {
+            assert!(matches!(
+                validate_key(key),
+                Err(WalletError::InvalidIntegrationKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_colliding_names_are_suffixed_and_kept() {
+        let v1 = format!(
+            "  /users:\n    get:\n{}  /users/all:\n    get:\n{}",
+            operation("list_users"),
+            operation("listUsers")
+        );
+        let first = stored(&v1);
+        assert_eq!(first.tool_names, ["test_list_users", "test_list_users_2"]);
+        assert_eq!(
+            first.integration.tool_name_collisions,
+            ["test_list_users_2"]
+        );
+
+        // A new operation taking the plain name does not rename existing tools
+        let v2 = format!(
+            "  /people:\n    get:\n{}{}",
+            operation("list.users"),
+            v1.trim_start_matches('\n')
+        );
+        let mut second = stored(&v2);
+        second.assign_tool_names(Some(&first));
+        assert_eq!(
+            second.tool_names,
+            ["test_list_users_3", "test_list_users", "test_list_users_2"]
+        );
+        assert_eq!(
+            second.integration.tool_name_collisions,
+            ["test_list_users_2", "test_list_users_3"]
+        );
+
+        let (index, name) = (2, "test_list_users_2");
+        assert_eq!(
+            second.tool_operation(index, name).unwrap().path,
+            "/users/all"
+        );
+        assert!(second.tool_operation(index, "test_list_users").is_none());
+    }
+}
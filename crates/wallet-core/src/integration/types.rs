@@ -1,10 +1,26 @@
 //! Integration type definitions
 
 use chrono::{DateTime, Utc};
-use openapi_parser::{ApiOperation, AuthScheme, NamespaceTree, ParsedSpec};
+use openapi_parser::{
+    ApiKeyLocation, ApiOperation, AuthScheme, HttpMethod, NamespaceTree, ParsedSpec,
+    SecurityRequirement, SecurityScheme, ServerInfo,
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
+use super::diff::SpecDiff;
+use super::filter::OperationFilter;
+use crate::credential::CredentialType;
+use crate::error::{Result, WalletError};
+
+/// Last path segments of operations that describe the caller
+const WHOAMI_SEGMENTS: &[&str] = &["me", "whoami", "user", "account", "viewer", "self"];
+
+/// Start of the error messages written by failed syncs
+pub(crate) const SYNC_ERROR_PREFIX: &str = "Sync failed: ";
+
 /// Status of an integration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -35,25 +51,133 @@ pub struct Integration {
     /// Description
     pub description: Option<String>,
 
+    /// Credential provider whose credentials fit the integration (defaults to the key)
+    #[serde(default)]
+    pub provider: Option<String>,
+
     /// Source OpenAPI spec URL (if fetched remotely)
     pub spec_url: Option<String>,
 
+    /// Source OpenAPI spec file (if loaded from disk; reloaded when it changes)
+    #[serde(default)]
+    pub spec_path: Option<String>,
+
     /// Base server URL
     pub server_url: String,
 
+    /// Index of the chosen server among the spec's servers
+    #[serde(default)]
+    pub server_index: usize,
+
+    /// Values chosen for the server's URL variables (defaults are used for the rest)
+    #[serde(default)]
+    pub server_variables: BTreeMap<String, String>,
+
+    /// Why the server must be chosen again (the spec no longer offers the chosen one)
+    #[serde(default)]
+    pub server_error: Option<String>,
+
     /// Current status
     pub status: IntegrationStatus,
 
-    /// Associated credential ID (if any)
+    /// Default credential ID (if any)
     pub credential_id: Option<Uuid>,
 
+    /// Labeled credentials that tool calls can select
+    #[serde(default)]
+    pub credential_bindings: Vec<CredentialBinding>,
+
+    /// Active environment; bindings for it are preferred over the default
+    #[serde(default)]
+    pub environment: Option<String>,
+
     /// Detected authentication scheme
-    #[serde(skip)]
+    #[serde(default)]
     pub auth_scheme: Option<AuthScheme>,
 
+    /// User-chosen authentication scheme that replaces the detected one
+    #[serde(default)]
+    pub auth_override: Option<AuthScheme>,
+
+    /// Request signing scheme (replaces header/query credential placement)
+    #[serde(default)]
+    pub signing: Option<SigningConfig>,
+
+    /// Multi-field credential mappings (field name -> apiKey security scheme name)
+    #[serde(default)]
+    pub credential_fields: BTreeMap<String, String>,
+
+    /// Operation called to check that a credential works (defaults to a guess)
+    #[serde(default)]
+    pub probe_operation: Option<String>,
+
+    /// Which operations are exposed as tools
+    #[serde(default)]
+    pub operation_filter: OperationFilter,
+
+    /// Tool names given a numeric suffix because another operation has the plain name
+    #[serde(default)]
+    pub tool_name_collisions: Vec<String>,
+
+    /// Check the credential against the probe operation this often (seconds)
+    #[serde(default)]
+    pub health_check_interval_secs: Option<u64>,
+
+    /// When the next periodic credential check is due
+    #[serde(default)]
+    pub next_health_check_at: Option<DateTime<Utc>>,
+
+    /// Server errors (5xx) in a row; enough of them mark the integration as errored
+    #[serde(default)]
+    pub consecutive_server_errors: u32,
+
+    /// Last time a credential was checked against the probe operation
+    #[serde(default)]
+    pub last_validated_at: Option<DateTime<Utc>>,
+
     /// Number of operations
     pub operation_count: usize,
 
+    /// Spec revision, incremented whenever the spec is replaced
+    #[serde(default)]
+    pub revision: u32,
+
+    /// API version declared by the spec (`info.version`)
+    #[serde(default)]
+    pub spec_version: Option<String>,
+
+    /// SHA-256 hash of the raw spec content (if it was kept)
+    #[serde(default)]
+    pub spec_hash: Option<String>,
+
+    /// Hash of a remote spec that was rolled back; syncs skip it until it changes
+    #[serde(default)]
+    pub rejected_spec_hash: Option<String>,
+
+    /// Hold back syncs with breaking changes until they are confirmed
+    #[serde(default)]
+    pub confirm_breaking_sync: bool,
+
+    /// Sync the remote spec in the background this often (seconds)
+    #[serde(default)]
+    pub sync_interval_secs: Option<u64>,
+
+    /// When the next background sync is due
+    #[serde(default)]
+    pub next_sync_at: Option<DateTime<Utc>>,
+
+    /// Consecutive failed syncs (background syncs back off while this grows)
+    #[serde(default)]
+    pub sync_failures: u32,
+
+    /// `ETag` of the last fetched remote spec
+    #[serde(default)]
+    pub spec_etag: Option<String>,
+
+    /// `Last-Modified` date of the last fetched remote spec
+    #[serde(default)]
+    pub spec_last_modified: Option<String>,
+
     /// Last sync timestamp
     pub last_synced_at: Option<DateTime<Utc>>,
 
@@ -68,12 +192,99 @@ pub struct Integration {
 }
 
 impl Integration {
+    /// Credential provider whose credentials fit the integration
+    pub fn credential_provider(&self) -> &str {
+        self.provider.as_deref().unwrap_or(&self.key)
+    }
+
+    /// Pick the credential binding for a request
+    ///
+    /// An explicit label must match a binding. Otherwise a binding for the
+    /// active environment wins, then the binding of the default credential.
+    /// `Ok(None)` means the default credential (if any) is used unbound.
+    pub fn select_binding(&self, label: Option<&str>) -> Result<Option<&CredentialBinding>> {
+        if let Some(label) = label {
+            return self
+                .credential_bindings
+                .iter()
+                .find(|b| b.label == label)
+                .map(Some)
+                .ok_or_else(|| {
+                    WalletError::CredentialNotFound(format!(
+                        "No credential labeled {} for integration {}",
+                        label, self.key
+                    ))
+                });
+        }
+
+        if let Some(environment) = &self.environment {
+            if let Some(binding) = self
+                .credential_bindings
+                .iter()
+                .find(|b| b.environment.as_ref() == Some(environment))
+            {
+                return Ok(Some(binding));
+            }
+        }
+
+        Ok(self
+            .credential_bindings
+            .iter()
+            .find(|b| Some(b.credential_id) == self.credential_id))
+    }
+
+    /// Whether `error` was written by a failed sync
+    pub fn has_sync_error(&self) -> bool {
+        self.error
+            .as_deref()
+            .is_some_and(|error| error.starts_with(SYNC_ERROR_PREFIX))
+    }
+
+    /// Whether a background sync is due
+    pub fn sync_due(&self, now: DateTime<Utc>) -> bool {
+        self.spec_url.is_some()
+            && self.sync_interval_secs.is_some()
+            && self.next_sync_at.is_none_or(|at| at <= now)
+    }
+
+    /// Whether a periodic credential check is due
+    pub fn health_check_due(&self, now: DateTime<Utc>) -> bool {
+        self.status != IntegrationStatus::Disabled
+            && self.health_check_interval_secs.is_some()
+            && self.next_health_check_at.is_none_or(|at| at <= now)
+    }
+
+    /// Where the current spec came from
+    pub fn spec_source(&self) -> SpecSource {
+        match (&self.spec_url, &self.spec_path) {
+            (Some(url), _) => SpecSource::Url { url: url.clone() },
+            (None, Some(path)) => SpecSource::File { path: path.clone() },
+            (None, None) => SpecSource::Content,
+        }
+    }
+
+    /// Summarize the current spec for a history listing
+    pub fn history_entry(&self) -> SpecHistoryEntry {
+        SpecHistoryEntry {
+            revision: self.revision,
+            source: self.spec_source(),
+            spec_version: self.spec_version.clone(),
+            spec_hash: self.spec_hash.clone(),
+            operation_count: self.operation_count,
+            synced_at: self.last_synced_at,
+            replaced_at: None,
+        }
+    }
+
     /// Create a new integration from a parsed spec
     pub fn from_spec(key: String, spec: &ParsedSpec) -> Self {
         let server_url = spec
             .servers
             .first()
-            .map(|s| s.url.clone())
+            .map(|s| {
+                s.url_with(&BTreeMap::new())
+                    .unwrap_or_else(|_| s.url.clone())
+            })
             .unwrap_or_default();
 
         Self {
@@ -81,12 +292,42 @@ impl Integration {
             key,
             name: spec.title.clone(),
             description: spec.description.clone(),
+            provider: None,
             spec_url: None,
+            spec_path: None,
             server_url,
+            server_index: 0,
+            server_variables: BTreeMap::new(),
+            server_error: None,
             status: IntegrationStatus::Pending,
             credential_id: None,
-            auth_scheme: None,
+            credential_bindings: Vec::new(),
+            environment: None,
+            auth_scheme: Some(AuthScheme::detect(
+                &spec.security_schemes,
+                &spec.global_security,
+            )),
+            auth_override: None,
+            signing: None,
+            credential_fields: BTreeMap::new(),
+            probe_operation: None,
+            operation_filter: OperationFilter::default(),
+            tool_name_collisions: Vec::new(),
+            health_check_interval_secs: None,
+            next_health_check_at: None,
+            consecutive_server_errors: 0,
+            last_validated_at: None,
             operation_count: spec.operations.len(),
+            revision: 1,
+            spec_version: Some(spec.version.clone()),
+            spec_hash: None,
+            rejected_spec_hash: None,
+            confirm_breaking_sync: false,
+            sync_interval_secs: None,
+            next_sync_at: None,
+            sync_failures: 0,
+            spec_etag: None,
+            spec_last_modified: None,
             last_synced_at: Some(Utc::now()),
             error: None,
             created_at: Utc::now(),
@@ -95,6 +336,237 @@ impl Integration {
     }
 }
 
+/// How requests to an integration are signed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SigningConfig {
+    /// AWS Signature Version 4
+    ///
+    /// Uses a multi-field credential with `access_key_id`,
+    /// `secret_access_key` and optionally `session_token`.
+    AwsSigV4 {
+        /// AWS region (e.g., "us-east-1")
+        region: String,
+        /// Service name used in the credential scope (e.g., "execute-api")
+        service: String,
+    },
+    /// HMAC-SHA256 over timestamp, method, path and body
+    ///
+    /// Uses an API key credential as the secret, or a multi-field
+    /// credential with `secret` and optionally `key_id`.
+    HmacSha256 {
+        /// Header that carries the hex signature
+        signature_header: String,
+        /// Header that carries the Unix timestamp
+        timestamp_header: String,
+        /// Header that carries the key ID (multi-field credentials only)
+        #[serde(default)]
+        key_id_header: Option<String>,
+    },
+}
+
+/// A labeled credential bound to an integration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialBinding {
+    /// Label used to select the credential (e.g., "live", "acme-corp")
+    pub label: String,
+
+    /// Bound credential ID
+    pub credential_id: Uuid,
+
+    /// Environment this credential belongs to (e.g., "test", "live")
+    #[serde(default)]
+    pub environment: Option<String>,
+
+    /// Server URL to call with this credential instead of the integration's
+    #[serde(default)]
+    pub server_url: Option<String>,
+}
+
+impl CredentialBinding {
+    /// Create a binding with no rules
+    pub fn new(label: &str, credential_id: Uuid) -> Self {
+        Self {
+            label: label.to_string(),
+            credential_id,
+            environment: None,
+            server_url: None,
+        }
+    }
+}
+
+/// Where a multi-field credential field is sent in a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialFieldTarget {
+    /// Credential field name
+    pub field: String,
+    /// Parameter location (header, query or cookie)
+    pub location: ApiKeyLocation,
+    /// Parameter name
+    pub name: String,
+}
+
+/// Outcome of syncing an integration with its remote spec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// Changes between the stored and the remote spec
+    pub diff: SpecDiff,
+
+    /// Whether the remote spec was applied (false while awaiting confirmation)
+    pub applied: bool,
+
+    /// Revision of the integration after the sync
+    pub revision: u32,
+}
+
+/// Where a spec version came from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpecSource {
+    /// Fetched from a URL
+    Url { url: String },
+    /// Loaded from a local file
+    File { path: String },
+    /// Provided as content
+    #[default]
+    Content,
+}
+
+/// A replaced version of an integration's spec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecRevision {
+    /// Revision number
+    pub revision: u32,
+
+    /// Where the spec came from
+    #[serde(default)]
+    pub source: SpecSource,
+
+    /// API version declared by the spec
+    #[serde(default)]
+    pub spec_version: Option<String>,
+
+    /// SHA-256 hash of the raw spec content (if it was kept)
+    #[serde(default)]
+    pub spec_hash: Option<String>,
+
+    /// Human-readable name at this revision
+    pub name: String,
+
+    /// Description at this revision
+    pub description: Option<String>,
+
+    /// Base server URL at this revision
+    pub server_url: String,
+
+    /// Servers listed by the spec at this revision
+    #[serde(default)]
+    pub servers: Vec<ServerInfo>,
+
+    /// Detected authentication scheme at this revision
+    pub auth_scheme: Option<AuthScheme>,
+
+    /// Parsed operations
+    pub operations: Vec<ApiOperation>,
+
+    /// Raw spec content (if it was kept)
+    pub spec_content: Option<String>,
+
+    /// Security schemes defined in the spec
+    pub security_schemes: HashMap<String, SecurityScheme>,
+
+    /// When this revision was synced
+    pub synced_at: Option<DateTime<Utc>>,
+
+    /// When this revision was replaced
+    pub replaced_at: DateTime<Utc>,
+}
+
+impl SpecRevision {
+    /// Snapshot the current spec of a stored integration
+    pub fn capture(stored: &StoredIntegration) -> Self {
+        Self {
+            revision: stored.integration.revision,
+            source: stored.integration.spec_source(),
+            spec_version: stored.integration.spec_version.clone(),
+            spec_hash: stored.integration.spec_hash.clone(),
+            name: stored.integration.name.clone(),
+            description: stored.integration.description.clone(),
+            server_url: stored.integration.server_url.clone(),
+            servers: stored.servers.clone(),
+            auth_scheme: stored.integration.auth_scheme.clone(),
+            operations: stored.operations.clone(),
+            spec_content: stored.spec_content.clone(),
+            security_schemes: stored.security_schemes.clone(),
+            synced_at: stored.integration.last_synced_at,
+            replaced_at: Utc::now(),
+        }
+    }
+
+    /// Summarize this revision for a history listing
+    pub fn summary(&self) -> SpecHistoryEntry {
+        SpecHistoryEntry {
+            revision: self.revision,
+            source: self.source.clone(),
+            spec_version: self.spec_version.clone(),
+            spec_hash: self.spec_hash.clone(),
+            operation_count: self.operations.len(),
+            synced_at: self.synced_at,
+            replaced_at: Some(self.replaced_at),
+        }
+    }
+}
+
+/// One version in an integration's spec history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecHistoryEntry {
+    /// Revision number
+    pub revision: u32,
+
+    /// Where the spec came from
+    pub source: SpecSource,
+
+    /// API version declared by the spec
+    pub spec_version: Option<String>,
+
+    /// SHA-256 hash of the raw spec content (if it was kept)
+    pub spec_hash: Option<String>,
+
+    /// Number of operations
+    pub operation_count: usize,
+
+    /// When this revision was synced
+    pub synced_at: Option<DateTime<Utc>>,
+
+    /// When this revision was replaced (`None` for the current revision)
+    pub replaced_at: Option<DateTime<Utc>>,
+}
+
+/// SHA-256 hash of raw spec content (hex encoded)
+pub fn spec_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// How well a scheme suits a credential type: 2 if made for it, 1 if it can be sent with it
+fn scheme_fit(scheme: &AuthScheme, credential_type: CredentialType) -> u8 {
+    match (credential_type, scheme) {
+        (
+            CredentialType::OAuth2Token | CredentialType::OAuth2ClientCredentials,
+            AuthScheme::OAuth2 { .. },
+        ) => 2,
+        (
+            CredentialType::OAuth2Token | CredentialType::OAuth2ClientCredentials,
+            AuthScheme::Bearer { .. },
+        ) => 1,
+        (CredentialType::BasicAuth, AuthScheme::Basic) => 2,
+        (CredentialType::ApiKey | CredentialType::External, AuthScheme::ApiKey { .. })
+        | (CredentialType::ApiKey | CredentialType::External, AuthScheme::Bearer { .. }) => 2,
+        // API keys may be personal access tokens of an OAuth2 API
+        (CredentialType::ApiKey | CredentialType::External, AuthScheme::OAuth2 { .. }) => 1,
+        _ => 0,
+    }
+}
+
 /// An operation within an integration (reference to parsed operation)
 pub type IntegrationOperation = ApiOperation;
 
@@ -112,27 +584,363 @@ pub struct StoredIntegration {
 
     /// Raw spec content (for re-parsing)
     pub spec_content: Option<String>,
+
+    /// Security schemes defined in the spec
+    #[serde(default)]
+    pub security_schemes: HashMap<String, SecurityScheme>,
+
+    /// Servers listed by the spec
+    #[serde(default)]
+    pub servers: Vec<ServerInfo>,
+
+    /// OpenAPI Overlay applied to the spec before parsing (kept across syncs)
+    #[serde(default)]
+    pub overlay: Option<String>,
+
+    /// Tool name of each operation (in the order of `operations`)
+    #[serde(default)]
+    pub tool_names: Vec<String>,
 }
 
 impl StoredIntegration {
     /// Create from a parsed spec
     pub fn from_spec(key: String, spec: ParsedSpec, spec_content: Option<String>) -> Self {
-        let integration = Integration::from_spec(key, &spec);
+        let mut integration = Integration::from_spec(key, &spec);
+        integration.spec_hash = spec_content.as_deref().map(spec_hash);
         let namespace = NamespaceTree::build(&spec.operations);
 
-        Self {
+        let mut stored = Self {
             integration,
             operations: spec.operations,
             namespace,
             spec_content,
+            security_schemes: spec.security_schemes,
+            servers: spec.servers,
+            overlay: None,
+            tool_names: Vec::new(),
+        };
+        stored.assign_tool_names(None);
+        stored
+    }
+
+    /// Base URL of the chosen server with its variables substituted
+    ///
+    /// A relative server URL is resolved against the spec URL. Integrations
+    /// saved without their server list keep their stored URL.
+    pub fn resolve_server_url(&self) -> Result<String> {
+        let integration = &self.integration;
+        if self.servers.is_empty() {
+            return Ok(integration.server_url.clone());
+        }
+
+        let server = self.servers.get(integration.server_index).ok_or_else(|| {
+            WalletError::InvalidSpec(format!(
+                "{} has no server {}",
+                integration.key, integration.server_index
+            ))
+        })?;
+        let url = server
+            .url_with(&integration.server_variables)
+            .map_err(|e| WalletError::InvalidSpec(e.to_string()))?;
+
+        match (url::Url::parse(&url), &integration.spec_url) {
+            (Err(url::ParseError::RelativeUrlWithoutBase), Some(spec_url)) => {
+                let base = url::Url::parse(spec_url)
+                    .map_err(|e| WalletError::InvalidSpec(format!("{}: {}", spec_url, e)))?;
+                let resolved = base
+                    .join(&url)
+                    .map_err(|e| WalletError::InvalidSpec(format!("{}: {}", url, e)))?;
+                Ok(resolved.as_str().trim_end_matches('/').to_string())
+            }
+            _ => Ok(url),
+        }
+    }
+
+    /// Recompute the server URL after the spec changed
+    ///
+    /// When the spec no longer offers the chosen server or variable value,
+    /// the previous URL is kept and `server_error` asks to choose again.
+    pub fn refresh_server_url(&mut self) {
+        match self.resolve_server_url() {
+            Ok(url) => {
+                self.integration.server_url = url;
+                self.integration.server_error = None;
+            }
+            Err(e) => {
+                self.integration.server_error = Some(format!(
+                    "The chosen server is no longer offered, choose one again ({})",
+                    e
+                ));
+            }
         }
     }
 
+    /// Get the authentication scheme used for requests
+    ///
+    /// Prefers the override, then the detected scheme. Integrations saved
+    /// before the scheme was persisted fall back to detecting it again.
+    pub fn auth_scheme(&self) -> AuthScheme {
+        self.integration
+            .auth_override
+            .clone()
+            .or_else(|| self.integration.auth_scheme.clone())
+            .unwrap_or_else(|| AuthScheme::detect(&self.security_schemes, &[]))
+    }
+
+    /// Get the authentication scheme for a single operation
+    ///
+    /// Public operations use no scheme. Otherwise the override wins, then
+    /// the operation's own security requirements, then the integration's scheme.
+    pub fn operation_auth_scheme(&self, operation: &ApiOperation) -> AuthScheme {
+        if operation.public {
+            return AuthScheme::None;
+        }
+        if let Some(scheme) = &self.integration.auth_override {
+            return scheme.clone();
+        }
+        if operation.security.is_empty() {
+            return self.auth_scheme();
+        }
+
+        match AuthScheme::detect(&self.security_schemes, &operation.security) {
+            // Requirements naming undefined schemes fall back to the integration's scheme
+            AuthScheme::None => self.auth_scheme(),
+            scheme => scheme,
+        }
+    }
+
+    /// Get the scheme to send a credential with for an operation
+    ///
+    /// Takes the first requirement, across the operation's alternatives,
+    /// whose scheme suits the credential type, else the first it can be sent
+    /// with. Returns `None` when the operation declares schemes and none
+    /// accepts the credential.
+    pub fn credential_auth_scheme(
+        &self,
+        operation: &ApiOperation,
+        credential_type: CredentialType,
+    ) -> Option<AuthScheme> {
+        if operation.public {
+            return Some(AuthScheme::None);
+        }
+        if let Some(scheme) = &self.integration.auth_override {
+            return Some(scheme.clone());
+        }
+
+        let declared: Vec<AuthScheme> = operation
+            .security
+            .iter()
+            .filter(|req| self.security_schemes.contains_key(&req.scheme_name))
+            .map(|req| AuthScheme::detect(&self.security_schemes, std::slice::from_ref(req)))
+            .collect();
+        let best = |schemes: &[AuthScheme]| {
+            [2, 1].into_iter().find_map(|fit| {
+                schemes
+                    .iter()
+                    .find(|scheme| scheme_fit(scheme, credential_type) == fit)
+                    .cloned()
+            })
+        };
+        if !declared.is_empty() {
+            return best(&declared);
+        }
+
+        // Requirements naming undefined schemes fall back to the integration's scheme
+        let fallback = match self.auth_scheme() {
+            AuthScheme::Multiple(schemes) => schemes,
+            scheme => vec![scheme],
+        };
+        Some(match best(&fallback) {
+            Some(scheme) => scheme,
+            None if credential_type == CredentialType::BasicAuth => AuthScheme::Basic,
+            None => fallback.into_iter().next().unwrap_or(AuthScheme::None),
+        })
+    }
+
+    /// Get the OAuth2 scheme used for interactive login
+    ///
+    /// Uses the detected scheme when it is OAuth2, otherwise the first
+    /// OAuth2 security scheme (by name) with an authorization-code flow.
+    pub fn oauth2_auth_scheme(&self) -> Option<AuthScheme> {
+        let detected = match &self.integration.auth_scheme {
+            Some(AuthScheme::Multiple(schemes)) => schemes
+                .iter()
+                .find(|s| matches!(s, AuthScheme::OAuth2 { .. }))
+                .cloned(),
+            Some(scheme @ AuthScheme::OAuth2 { .. }) => Some(scheme.clone()),
+            _ => None,
+        };
+        if detected.is_some() {
+            return detected;
+        }
+
+        let mut names: Vec<&String> = self.security_schemes.keys().collect();
+        names.sort();
+
+        names
+            .into_iter()
+            .find_map(|name| match &self.security_schemes[name] {
+                SecurityScheme::OAuth2 { flows } => {
+                    flows
+                        .authorization_code
+                        .as_ref()
+                        .map(|flow| AuthScheme::OAuth2 {
+                            authorization_url: flow.authorization_url.clone(),
+                            token_url: flow.token_url.clone(),
+                            scopes: Vec::new(),
+                        })
+                }
+                _ => None,
+            })
+    }
+
+    /// Get the OAuth2 token endpoint from the spec's security schemes
+    ///
+    /// Prefers the flow's `refreshUrl`, then its `tokenUrl`. Schemes are
+    /// checked in name order so the result is stable across reloads.
+    pub fn oauth2_token_url(&self) -> Option<String> {
+        let mut names: Vec<&String> = self.security_schemes.keys().collect();
+        names.sort();
+
+        names
+            .into_iter()
+            .find_map(|name| match &self.security_schemes[name] {
+                SecurityScheme::OAuth2 { flows } => [
+                    &flows.authorization_code,
+                    &flows.password,
+                    &flows.client_credentials,
+                ]
+                .into_iter()
+                .flatten()
+                .find_map(|flow| flow.refresh_url.clone().or_else(|| flow.token_url.clone())),
+                _ => None,
+            })
+    }
+
+    /// Get the token endpoint of the spec's client credentials flow
+    pub fn client_credentials_token_url(&self) -> Option<String> {
+        let mut names: Vec<&String> = self.security_schemes.keys().collect();
+        names.sort();
+
+        names
+            .into_iter()
+            .find_map(|name| match &self.security_schemes[name] {
+                SecurityScheme::OAuth2 { flows } => flows
+                    .client_credentials
+                    .as_ref()
+                    .and_then(|flow| flow.token_url.clone()),
+                _ => None,
+            })
+    }
+
+    /// Get the OAuth2 scopes an operation's security requirements ask for
+    ///
+    /// Only the first alternative with an OAuth2 scheme counts; the scopes of
+    /// other alternatives are not needed when that one is met.
+    pub fn oauth2_scopes(&self, operation: &ApiOperation) -> Vec<String> {
+        let is_oauth2 = |req: &&SecurityRequirement| {
+            matches!(
+                self.security_schemes.get(&req.scheme_name),
+                Some(SecurityScheme::OAuth2 { .. } | SecurityScheme::OpenIdConnect { .. })
+            )
+        };
+        let Some(alternative) = operation
+            .security
+            .iter()
+            .find(is_oauth2)
+            .map(|req| req.alternative)
+        else {
+            return Vec::new();
+        };
+
+        let mut scopes: Vec<String> = operation
+            .security
+            .iter()
+            .filter(|req| req.alternative == alternative)
+            .filter(is_oauth2)
+            .flat_map(|req| req.scopes.iter().cloned())
+            .collect();
+
+        scopes.sort();
+        scopes.dedup();
+        scopes
+    }
+
+    /// Resolve where each credential field is sent
+    ///
+    /// Fields use their explicit mapping, or else the apiKey security scheme
+    /// with the same name. Fields that resolve to no apiKey scheme are skipped.
+    pub fn credential_field_targets(&self, fields: &[String]) -> Vec<CredentialFieldTarget> {
+        fields
+            .iter()
+            .filter_map(|field| {
+                let scheme_name = self
+                    .integration
+                    .credential_fields
+                    .get(field)
+                    .unwrap_or(field);
+
+                match self.security_schemes.get(scheme_name) {
+                    Some(SecurityScheme::ApiKey { name, location }) => {
+                        Some(CredentialFieldTarget {
+                            field: field.clone(),
+                            location: *location,
+                            name: name.clone(),
+                        })
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Pick the operation used to check that a credential works
+    ///
+    /// Uses the configured probe operation, or else a GET without required
+    /// parameters, preferring "whoami"-style paths such as `/me` or `/user`.
+    pub fn probe_operation(&self) -> Option<String> {
+        if let Some(path) = &self.integration.probe_operation {
+            return Some(path.clone());
+        }
+
+        let mut candidates: Vec<(String, &ApiOperation)> = self
+            .operation_paths()
+            .into_iter()
+            .filter_map(|path| self.lookup_operation(&path).map(|op| (path, op)))
+            .filter(|(_, op)| {
+                op.method == HttpMethod::Get
+                    && !op.public
+                    && op.parameters.iter().all(|p| !p.required)
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.0.cmp(&b.0));
+
+        candidates
+            .iter()
+            .find(|(_, op)| {
+                op.path.rsplit('/').next().is_some_and(|segment| {
+                    WHOAMI_SEGMENTS.contains(&segment.to_lowercase().as_str())
+                })
+            })
+            .or(candidates.first())
+            .map(|(path, _)| path.clone())
+    }
+
+    /// Operations exposed by the integration's filter
+    pub fn allowed_operations(&self) -> impl Iterator<Item = &ApiOperation> {
+        self.operations
+            .iter()
+            .filter(|op| self.integration.operation_filter.allows(op))
+    }
+
     /// Look up an operation by namespace path
+    ///
+    /// Operations hidden by the integration's filter are not found.
     pub fn lookup_operation(&self, path: &str) -> Option<&ApiOperation> {
         self.namespace
             .lookup(path)
             .map(|op_ref| &self.operations[op_ref.index])
+            .filter(|op| self.integration.operation_filter.allows(op))
     }
 
     /// List all operations under a namespace prefix
@@ -141,11 +949,16 @@ impl StoredIntegration {
             .list(prefix)
             .iter()
             .map(|op_ref| &self.operations[op_ref.index])
+            .filter(|op| self.integration.operation_filter.allows(op))
             .collect()
     }
 
     /// Get all operation paths
     pub fn operation_paths(&self) -> Vec<String> {
-        self.namespace.paths()
+        self.namespace
+            .paths()
+            .into_iter()
+            .filter(|path| self.lookup_operation(path).is_some())
+            .collect()
     }
 }
@@ -10,17 +10,27 @@ pub mod credential;
 pub mod crypto;
 pub mod error;
 pub mod integration;
+pub mod redact;
 pub mod session;
 pub mod settings;
 pub mod storage;
 mod wallet;
 
-pub use credential::{Credential, CredentialManager, CredentialType, DecryptedCredential};
+pub use credential::{
+    suggest_provider, with_requester, AuditAction, AuditEntry, Credential, CredentialManager,
+    CredentialType, CredentialUsage, DecryptedCredential, ExternalSecret, ExternalSource,
+    TokenLookup,
+};
 pub use crypto::{decrypt, decrypt_string, encrypt, encrypt_string, generate_salt, MasterKey};
 pub use error::{Result, WalletError};
+pub use integration::{tool_name, validate_key};
 pub use integration::{
-    Integration, IntegrationOperation, IntegrationRegistry, IntegrationStatus, StoredIntegration,
+    CredentialBinding, HealthOutcome, Integration, IntegrationOperation, IntegrationRegistry,
+    IntegrationStatus, IntegrationTemplate, OperationFilter, OperationMatcher, SigningConfig,
+    SpecChange, SpecDiff, SpecHistoryEntry, SpecRevision, SpecSource, StoredIntegration,
+    SyncReport, SERVER_ERROR_THRESHOLD,
 };
+pub use redact::{redact, RedactingWriter, Redactor};
 pub use session::{Session, SessionManager};
 pub use settings::{OtelSettings, Settings, SettingsManager};
 pub use storage::{EncryptedFileStorage, KeychainStorage, SecureStorage};
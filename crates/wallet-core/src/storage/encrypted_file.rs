@@ -333,10 +333,10 @@ impl Default for EncryptedFileStorage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crypto::key_derivation::{derive_key, generate_salt};
+    use crate::crypto::{derive_key, generate_salt};
     use tempfile::TempDir;
 
-    async fn test_storage() -> (EncryptedFileStorage, MasterKey) {
+    async fn test_storage() -> (EncryptedFileStorage, MasterKey, TempDir) {
         let temp_dir = TempDir::new().unwrap();
         let storage = EncryptedFileStorage::with_dir(temp_dir.path().to_path_buf()).unwrap();
 
@@ -345,12 +345,12 @@ mod tests {
 
         storage.set_master_key(Some(key.clone())).await;
 
-        (storage, key)
+        (storage, key, temp_dir)
     }
 
     #[tokio::test]
     async fn test_store_and_retrieve() {
-        let (storage, _) = test_storage().await;
+        let (storage, _, _temp) = test_storage().await;
 
         storage.store("test-key", b"test-value").await.unwrap();
 
@@ -360,7 +360,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_retrieve_nonexistent() {
-        let (storage, _) = test_storage().await;
+        let (storage, _, _temp) = test_storage().await;
 
         let retrieved = storage.retrieve("nonexistent").await.unwrap();
         assert_eq!(retrieved, None);
@@ -368,7 +368,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete() {
-        let (storage, _) = test_storage().await;
+        let (storage, _, _temp) = test_storage().await;
 
         storage.store("test-key", b"test-value").await.unwrap();
         storage.delete("test-key").await.unwrap();
@@ -379,7 +379,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_exists() {
-        let (storage, _) = test_storage().await;
+        let (storage, _, _temp) = test_storage().await;
 
         assert!(!storage.exists("test-key").await.unwrap());
 
@@ -390,7 +390,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_keys() {
-        let (storage, _) = test_storage().await;
+        let (storage, _, _temp) = test_storage().await;
 
         storage.store("cred:openai", b"key1").await.unwrap();
         storage.store("cred:anthropic", b"key2").await.unwrap();
@@ -404,7 +404,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_clear() {
-        let (storage, _) = test_storage().await;
+        let (storage, _, _temp) = test_storage().await;
 
         storage.store("key1", b"value1").await.unwrap();
         storage.store("key2", b"value2").await.unwrap();
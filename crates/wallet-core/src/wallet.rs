@@ -1,9 +1,13 @@
 //! Main wallet orchestration
 
 use std::sync::Arc;
-use tracing::{debug, info};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
 
-use crate::credential::CredentialManager;
+use crate::credential::{
+    AuthorizationCodeFlow, Credential, CredentialManager, PendingAuthorization, TokenResponse,
+};
 use crate::crypto::{derive_key, generate_salt, MasterKey};
 use crate::error::{Result, WalletError};
 use crate::integration::IntegrationRegistry;
@@ -11,6 +15,12 @@ use crate::session::{Session, SessionManager};
 use crate::settings::{OtelSettings, Settings, SettingsManager};
 use crate::storage::{EncryptedFileStorage, SecureStorage};
 
+/// Audit log file name (in the storage directory)
+const AUDIT_LOG_FILE: &str = "audit.log";
+
+/// User catalog of integration templates (in the storage directory)
+const TEMPLATE_CATALOG_FILE: &str = "templates.json";
+
 /// Wallet state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WalletState {
@@ -53,8 +63,10 @@ impl Wallet {
 
         let session_manager = SessionManager::new(storage.storage_dir());
         let settings_manager = SettingsManager::new(storage.storage_dir());
-        let integrations = IntegrationRegistry::new(storage.clone());
-        let credentials = CredentialManager::new(storage.clone());
+        let integrations = IntegrationRegistry::new(storage.clone())
+            .with_catalog_file(storage.storage_dir().join(TEMPLATE_CATALOG_FILE));
+        let credentials = CredentialManager::new(storage.clone())
+            .with_audit_log(storage.storage_dir().join(AUDIT_LOG_FILE));
 
         Ok(Self {
             storage,
@@ -77,8 +89,10 @@ impl Wallet {
 
         let session_manager = SessionManager::new(storage.storage_dir());
         let settings_manager = SettingsManager::new(storage.storage_dir());
-        let integrations = IntegrationRegistry::new(storage.clone());
-        let credentials = CredentialManager::new(storage.clone());
+        let integrations = IntegrationRegistry::new(storage.clone())
+            .with_catalog_file(storage.storage_dir().join(TEMPLATE_CATALOG_FILE));
+        let credentials = CredentialManager::new(storage.clone())
+            .with_audit_log(storage.storage_dir().join(AUDIT_LOG_FILE));
 
         Self {
             storage,
@@ -257,6 +271,104 @@ impl Wallet {
         self.session_manager.clear_session().await
     }
 
+    /// Start an OAuth2 authorization-code login for an integration
+    ///
+    /// Open the returned authorization URL in a browser, wait for the
+    /// tokens with `PendingAuthorization::wait_for_tokens`, then store them
+    /// with `save_oauth2_login`.
+    pub async fn begin_oauth2_login(
+        &self,
+        integration_key: &str,
+        client_id: &str,
+        client_secret: Option<&str>,
+    ) -> Result<PendingAuthorization> {
+        if self.state != WalletState::Unlocked {
+            return Err(WalletError::WalletLocked);
+        }
+
+        let stored = self
+            .integrations
+            .get_stored(integration_key)
+            .await
+            .ok_or_else(|| WalletError::IntegrationNotFound(integration_key.to_string()))?;
+
+        let scheme = stored.oauth2_auth_scheme().ok_or_else(|| {
+            WalletError::OAuth2Error(format!(
+                "Integration {} has no OAuth2 authorization-code flow",
+                integration_key
+            ))
+        })?;
+
+        AuthorizationCodeFlow::from_auth_scheme(&scheme, client_id, client_secret)?
+            .start()
+            .await
+    }
+
+    /// Store the tokens from a completed OAuth2 login and bind them to the integration
+    pub async fn save_oauth2_login(
+        &self,
+        integration_key: &str,
+        flow: &AuthorizationCodeFlow,
+        tokens: &TokenResponse,
+    ) -> Result<Credential> {
+        let integration = self
+            .integrations
+            .get(integration_key)
+            .await
+            .ok_or_else(|| WalletError::IntegrationNotFound(integration_key.to_string()))?;
+
+        let credential = self
+            .credentials
+            .add_oauth2_token(
+                integration_key,
+                &format!("{} (OAuth2)", integration.name),
+                &tokens.access_token,
+                tokens.refresh_token.as_deref(),
+                tokens.expires_at(),
+            )
+            .await?;
+
+        // The client is stored as a credential of its own and refreshes the token
+        let mut client_fields = vec![("client_id", flow.client_id.as_str())];
+        if let Some(client_secret) = &flow.client_secret {
+            client_fields.push(("client_secret", client_secret.expose()));
+        }
+        let client = self
+            .credentials
+            .add_multi_field(
+                integration_key,
+                &format!("{} (OAuth2 client)", integration.name),
+                &client_fields,
+            )
+            .await?;
+        self.credentials
+            .set_oauth2_client_credential(credential.id, Some(&flow.token_url), client.id)
+            .await?;
+
+        // Without a scope in the response, the requested scopes were granted
+        self.credentials
+            .set_granted_scopes(
+                credential.id,
+                Some(tokens.scopes().unwrap_or_else(|| flow.scopes.clone())),
+            )
+            .await?;
+
+        let credential = self
+            .credentials
+            .set_integration(credential.id, Some(integration.id))
+            .await?;
+
+        self.integrations
+            .set_credential(integration_key, credential.id)
+            .await?;
+
+        info!(
+            "Completed OAuth2 login for integration: {}",
+            integration_key
+        );
+        Ok(credential)
+    }
+
     /// Get current settings
     pub fn get_settings(&self) -> &Settings {
         self.settings_manager.get()
@@ -354,6 +466,73 @@ impl Wallet {
         keychain.is_available()
     }
 
+    /// Run scheduled background syncs of remote specs until the task is dropped
+    ///
+    /// Every `tick` the due integrations are synced, provided the wallet is
+    /// unlocked. Spawn this on the application's runtime, in one process only.
+    pub async fn run_sync_scheduler(wallet: Arc<RwLock<Wallet>>, tick: Duration) {
+        let mut interval = tokio::time::interval(tick);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            // Fetch without holding the wallet lock
+            let integrations = {
+                let wallet = wallet.read().await;
+                if !wallet.is_unlocked() {
+                    continue;
+                }
+                wallet.integrations.clone()
+            };
+
+            for (key, result) in integrations.sync_due().await {
+                if let Ok(report) = result {
+                    debug!(
+                        "Background sync of {}: revision {} ({} changes)",
+                        key,
+                        report.revision,
+                        report.diff.changes.len()
+                    );
+                }
+            }
+        }
+    }
+
+    /// Reload integrations whose local spec files change until the task is dropped
+    ///
+    /// Every `tick` the spec files are checked, provided the wallet is
+    /// unlocked. Spawn this on the application's runtime, in one process only.
+    pub async fn run_file_watcher(wallet: Arc<RwLock<Wallet>>, tick: Duration) {
+        let mut interval = tokio::time::interval(tick);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            // Read the files without holding the wallet lock
+            let integrations = {
+                let wallet = wallet.read().await;
+                if !wallet.is_unlocked() {
+                    continue;
+                }
+                wallet.integrations.clone()
+            };
+
+            for (key, result) in integrations.reload_changed_files().await {
+                match result {
+                    Ok(report) => info!(
+                        "Reloaded spec file of {}: revision {} ({} changes)",
+                        key,
+                        report.revision,
+                        report.diff.changes.len()
+                    ),
+                    Err(e) => warn!("Failed to reload spec file of {}: {}", key, e),
+                }
+            }
+        }
+    }
+
     /// Reset the wallet completely - deletes ALL data including integrations, credentials, and settings
     /// WARNING: This is irreversible!
     pub async fn reset(&mut self) -> Result<()> {
@@ -365,6 +544,9 @@ impl Wallet {
         // Clear session
         let _ = self.session_manager.clear_session().await;
 
+        // Clear audit log
+        self.credentials.clear_audit_log().await?;
+
         // Reset settings
         self.settings_manager.reset().await?;
 
@@ -383,7 +565,7 @@ impl Wallet {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::credential::CredentialType;
     use tempfile::TempDir;
 
     async fn test_wallet() -> (Wallet, TempDir) {
@@ -443,4 +625,89 @@ mod tests {
         wallet.unlock("new-password").await.unwrap();
         assert_eq!(wallet.state(), WalletState::Unlocked);
     }
+
+    #[tokio::test]
+    async fn test_oauth2_login_binds_credential() {
+        let (mut wallet, _temp) = test_wallet().await;
+        wallet.initialize("test-password").await.unwrap();
+
+        let spec = r#"
+openapi: "3.0.0"
+info:
+  title: OAuth API
+  version: "1.0.0"
+servers:
+  - url: https://api.example.com
+paths: {}
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        authorizationCode:
+          authorizationUrl: https://auth.example.com/authorize
+          tokenUrl: https://auth.example.com/token
+          scopes:
+            read: Read access
+security:
+  - oauth: [read]
+"#;
+        wallet
+            .integrations
+            .add_from_content("oauth", spec)
+            .await
+            .unwrap();
+
+        let pending = wallet
+            .begin_oauth2_login("oauth", "client-1", Some("secret-1"))
+            .await
+            .unwrap();
+        assert!(pending
+            .authorization_url()
+            .starts_with("https://auth.example.com/authorize?"));
+        assert!(pending.authorization_url().contains("scope=read"));
+
+        let tokens: TokenResponse = serde_json::from_value(serde_json::json!({
+            "access_token": "at-1",
+            "refresh_token": "rt-1",
+            "expires_in": 3600
+        }))
+        .unwrap();
+        let credential = wallet
+            .save_oauth2_login("oauth", pending.flow(), &tokens)
+            .await
+            .unwrap();
+
+        let integration = wallet.integrations.get("oauth").await.unwrap();
+        assert_eq!(integration.credential_id, Some(credential.id));
+        assert_eq!(credential.integration_id, Some(integration.id));
+        assert_eq!(credential.granted_scopes, Some(vec!["read".to_string()]));
+        assert_eq!(
+            wallet
+                .credentials
+                .get_decrypted(credential.id)
+                .await
+                .unwrap()
+                .expose(),
+            "at-1"
+        );
+        assert!(wallet
+            .credentials
+            .can_refresh(credential.id, None)
+            .await
+            .unwrap());
+
+        // The client ID and secret are a credential of their own
+        let client = wallet
+            .credentials
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|c| c.credential_type == CredentialType::MultiField)
+            .unwrap();
+        let fields = wallet.credentials.get_fields(client.id).await.unwrap();
+        assert_eq!(fields["client_id"].expose(), "client-1");
+        assert_eq!(fields["client_secret"].expose(), "secret-1");
+    }
 }
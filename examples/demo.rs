@@ -177,7 +177,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("6. Generating MCP tools:");
     let stored = wallet.integrations.get_stored("petstore").await.unwrap();
     let generator = ToolGenerator::new();
-    let tools = generator.generate_tools("petstore", &stored);
+    let tools = generator.generate_tools(&stored);
 
     for tool in &tools {
         println!("\n   Tool: {}", tool.name);
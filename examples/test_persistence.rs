@@ -33,7 +33,7 @@ async fn main() {
         // Check schema before save
         let stored = wallet.integrations.get_stored("openai").await.unwrap();
         let generator = ToolGenerator::new();
-        let tools = generator.generate_tools("openai", &stored);
+        let tools = generator.generate_tools(&stored);
 
         let chat_tool = tools
             .iter()
@@ -104,7 +104,7 @@ async fn main() {
 
                 // Generate tools
                 let generator = ToolGenerator::new();
-                let tools = generator.generate_tools("openai", &s);
+                let tools = generator.generate_tools(&s);
 
                 let chat_tool = tools
                     .iter()